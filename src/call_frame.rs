@@ -18,13 +18,13 @@ impl CallFrame {
         self.ip -= offset;
     }
 
-    pub fn next(&mut self) -> Option<&Instruction> {
+    pub fn next_instruction(&mut self) -> Option<&Instruction> {
         let instruction = self.closure.function.chunk.get_instruction(self.ip);
         self.ip += 1;
         instruction
     }
 
-    pub fn peek(&self) -> Option<&Instruction> {
+    pub fn peek_instruction(&self) -> Option<&Instruction> {
         self.closure.function.chunk.get_instruction(self.ip)
     }
 }
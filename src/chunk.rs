@@ -1,28 +1,44 @@
 use crate::{function::Function, value::Value};
-use std::{fmt, rc::Rc};
+use std::{collections::HashMap, fmt, rc::Rc};
 
 #[derive(Debug, Clone)]
 pub enum OpCode {
     Return,
     Call(usize),
+    CallSpread,
     Value(Value),
     Negate,
     Add,
+    Stringify,
     Subtract,
     Multiply,
     Divide,
+    IntegerDivide,
+    Modulo,
+    Power,
+    BitAnd,
+    BitOr,
+    BitXor,
+    BitNot,
+    ShiftLeft,
+    ShiftRight,
     Not,
     Equal,
     Greater,
     Less,
     Print,
     DefineGlobal(String),
+    DefineConstant(String),
     GetGlobal(String),
     SetGlobal(String),
     SetLocal(usize),
     GetLocal(usize),
     Pop,
+    Dup(usize),
+    Bury(usize),
     JumpIfFalse(usize),
+    JumpIfNil(usize),
+    JumpIfNotNil(usize),
     Jump(usize),
     Loop(usize),
     Closure(Rc<Function>),
@@ -31,13 +47,88 @@ pub enum OpCode {
     Upvalue(usize, bool),
     CloseUpvalue,
     Class(String),
+    Trait(String),
+    /// Copies the methods of the given number of traits into the class
+    /// beneath them. Members the class declares itself are listed, since
+    /// they settle conflicts between traits.
+    Implement(usize, Vec<String>),
+    /// Copies a record instance, replacing the fields named in a list of
+    /// name and value pairs.
+    CopyWith,
     SetProperty(String),
     GetProperty(String),
     Method(String),
+    StaticMethod(String),
+    Getter(String),
+    Setter(String),
+    /// Declares a method without a body that subclasses must implement.
+    AbstractMethod(String),
+    /// Declares an instance field; the closure computes its initial value
+    /// for every new instance.
+    Field(String),
+    StaticField(String),
     Invoke(String, usize),
     Inherit,
     GetSuper(String),
+    SetSuper(String),
     SuperInvoke(String, usize),
+    Yield,
+    BuildList(usize),
+    ExtendList,
+    /// Builds a map from the given number of key and value pairs, each key
+    /// pushed before its value.
+    BuildMap(usize),
+    BuildTuple(usize),
+    /// Builds a range from its start, end and step, any of them nil when
+    /// left out. The flag tells whether the end is included.
+    BuildRange(bool),
+    /// Pushes the elements of a tuple or list, which must have exactly the
+    /// given count, or at least that many followed by a rest list.
+    Unpack(usize, bool),
+    GetIndex,
+    SetIndex,
+    GetIterator,
+    IteratorNext(usize),
+    Switch(Rc<SwitchTable>),
+    InRange(bool),
+    Is,
+    NoMatch,
+}
+
+/// A literal a `match` can dispatch on with a single lookup.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SwitchKey {
+    Nil,
+    Bool(bool),
+    /// The bits of a number, with `-0` folded into `0` so both keys compare
+    /// the way `==` does.
+    Number(u64),
+    String(Rc<String>),
+}
+
+impl SwitchKey {
+    /// The key `value` is looked up by, if it can equal any literal. `NaN`
+    /// equals nothing, so it has no key either.
+    pub fn from_value(value: &Value) -> Option<SwitchKey> {
+        match value {
+            Value::Nil => Some(SwitchKey::Nil),
+            Value::Bool(b) => Some(SwitchKey::Bool(*b)),
+            Value::Number(n) if n.is_nan() => None,
+            Value::Number(n) if *n == 0.0 => Some(SwitchKey::Number(0f64.to_bits())),
+            Value::Number(n) => Some(SwitchKey::Number(n.to_bits())),
+            Value::String(s) => Some(SwitchKey::String(Rc::clone(s))),
+            _ => None,
+        }
+    }
+}
+
+/// Jump offsets of a `match` whose arms are all literals. Offsets are counted
+/// from the instruction after the `Switch`, like `Jump`. An instance whose
+/// class defines `__eq__` doesn't jump, and continues at that instruction.
+#[derive(Debug)]
+pub struct SwitchTable {
+    pub cases: HashMap<SwitchKey, usize>,
+    pub default: usize,
 }
 
 fn format_function(function: &Function) -> String {
@@ -77,36 +168,83 @@ impl fmt::Display for Instruction {
             OpCode::Subtract => "SUBTRACT".to_string(),
             OpCode::Multiply => "MULTIPLY".to_string(),
             OpCode::Divide => "DIVIDE".to_string(),
+            OpCode::IntegerDivide => "INTEGER_DIVIDE".to_string(),
+            OpCode::Modulo => "MODULO".to_string(),
+            OpCode::Power => "POWER".to_string(),
+            OpCode::BitAnd => "BIT_AND".to_string(),
+            OpCode::BitOr => "BIT_OR".to_string(),
+            OpCode::BitXor => "BIT_XOR".to_string(),
+            OpCode::BitNot => "BIT_NOT".to_string(),
+            OpCode::ShiftLeft => "SHIFT_LEFT".to_string(),
+            OpCode::ShiftRight => "SHIFT_RIGHT".to_string(),
             OpCode::Not => "NOT".to_string(),
             OpCode::Equal => "EQUAL".to_string(),
             OpCode::Greater => "GREATER".to_string(),
             OpCode::Less => "LESS".to_string(),
             OpCode::Print => "PRINT".to_string(),
             OpCode::DefineGlobal(name) => format!("DEFINE_GLOBAL {}", name),
+            OpCode::DefineConstant(name) => format!("DEFINE_CONSTANT {}", name),
             OpCode::GetGlobal(name) => format!("GET_GLOBAL {}", name),
             OpCode::SetGlobal(name) => format!("SET_GLOBAL {}", name),
             OpCode::SetLocal(index) => format!("SET_LOCAL {}", index),
             OpCode::GetLocal(index) => format!("GET_LOCAL {}", index),
             OpCode::Pop => "POP".to_string(),
+            OpCode::Dup(depth) => format!("DUP {}", depth),
+            OpCode::Bury(depth) => format!("BURY {}", depth),
             OpCode::JumpIfFalse(offset) => format!("JUMP_IF_FALSE {}", offset),
+            OpCode::JumpIfNil(offset) => format!("JUMP_IF_NIL {}", offset),
+            OpCode::JumpIfNotNil(offset) => format!("JUMP_IF_NOT_NIL {}", offset),
             OpCode::Jump(offset) => format!("JUMP {}", offset),
             OpCode::Loop(offset) => format!("LOOP {}", offset),
             OpCode::Call(arg_count) => format!("CALL {}", arg_count),
+            OpCode::CallSpread => "CALL_SPREAD".to_string(),
             OpCode::Closure(function) => format!("CLOSURE {}", format_function(function)),
             OpCode::GetUpvalue(index) => format!("GET_UPVALUE {}", index),
             OpCode::SetUpvalue(index) => format!("SET_UPVALUE {}", index),
             OpCode::Upvalue(index, is_local) => format!("UPVALUE {} {}", index, is_local),
             OpCode::CloseUpvalue => "CLOSE_UPVALUE".to_string(),
             OpCode::Class(name) => format!("CLASS {}", name),
+            OpCode::Trait(name) => format!("TRAIT {}", name),
+            OpCode::Implement(count, _) => format!("IMPLEMENT {}", count),
+            OpCode::CopyWith => "COPY_WITH".to_string(),
             OpCode::SetProperty(name) => format!("SET_PROPERTY {}", name),
             OpCode::GetProperty(name) => format!("GET_PROPERTY {}", name),
             OpCode::Method(name) => format!("METHOD {}", name),
+            OpCode::StaticMethod(name) => format!("STATIC_METHOD {}", name),
+            OpCode::Getter(name) => format!("GETTER {}", name),
+            OpCode::Setter(name) => format!("SETTER {}", name),
+            OpCode::AbstractMethod(name) => format!("ABSTRACT_METHOD {}", name),
+            OpCode::Field(name) => format!("FIELD {}", name),
+            OpCode::StaticField(name) => format!("STATIC_FIELD {}", name),
             OpCode::Invoke(name, arg_count) => format!("INVOKE {} ({})", name, arg_count),
             OpCode::Inherit => "INHERIT".to_string(),
             OpCode::GetSuper(name) => format!("GET_SUPER {}", name),
+            OpCode::SetSuper(name) => format!("SET_SUPER {}", name),
             OpCode::SuperInvoke(name, arg_count) => {
                 format!("SUPER_INVOKE {} ({})", name, arg_count)
             }
+            OpCode::Stringify => "STRINGIFY".to_string(),
+            OpCode::Yield => "YIELD".to_string(),
+            OpCode::BuildList(count) => format!("BUILD_LIST {}", count),
+            OpCode::BuildMap(count) => format!("BUILD_MAP {}", count),
+            OpCode::ExtendList => "EXTEND_LIST".to_string(),
+            OpCode::BuildTuple(count) => format!("BUILD_TUPLE {}", count),
+            OpCode::BuildRange(inclusive) => {
+                format!("BUILD_RANGE {}", if *inclusive { ".." } else { "..<" })
+            }
+            OpCode::Unpack(count, rest) => format!("UNPACK {} {}", count, rest),
+            OpCode::GetIndex => "GET_INDEX".to_string(),
+            OpCode::SetIndex => "SET_INDEX".to_string(),
+            OpCode::GetIterator => "GET_ITERATOR".to_string(),
+            OpCode::IteratorNext(offset) => format!("ITERATOR_NEXT {}", offset),
+            OpCode::Switch(table) => format!(
+                "SWITCH {} cases, default {}",
+                table.cases.len(),
+                table.default
+            ),
+            OpCode::InRange(inclusive) => format!("IN_RANGE {}", inclusive),
+            OpCode::Is => "IS".to_string(),
+            OpCode::NoMatch => "NO_MATCH".to_string(),
         };
 
         write!(f, "line {:3}: {}", self.line, op_str)
@@ -118,6 +256,12 @@ pub struct Chunk {
     instructions: Vec<Instruction>,
 }
 
+impl Default for Chunk {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Chunk {
     pub fn new() -> Chunk {
         Chunk {
@@ -130,11 +274,11 @@ impl Chunk {
     }
 
     pub fn disassemble(&self, name: &str) -> String {
-        let mut result = String::from(format!("== {} ==\n", name));
+        let mut result = format!("== {} ==\n", name);
 
         for instruction in self.instructions.iter() {
             result.push_str(&format!("{}", instruction));
-            result.push_str("\n");
+            result.push('\n');
         }
 
         result
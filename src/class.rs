@@ -1,11 +1,50 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap},
+    rc::Rc,
+};
 
 use crate::{closure::Closure, value::Value};
 
+/// The key a private member `#name` declared in `class` is stored under.
+/// Qualifying it with the class keeps a subclass's `#name` apart from the
+/// superclass's.
+pub fn private_key(class: &str, name: &str) -> String {
+    format!("{}{}", class, name)
+}
+
+/// Splits a private member's key into the declaring class and the `#name`
+/// written in the source. Public names give `None`.
+pub fn split_private_key(key: &str) -> Option<(&str, &str)> {
+    key.find('#').map(|index| key.split_at(index))
+}
+
+/// A member's name as written in the source.
+pub fn member_name(key: &str) -> &str {
+    split_private_key(key).map_or(key, |(_, name)| name)
+}
+
 #[derive(Debug, Clone)]
 pub struct Class {
     pub name: String,
     pub methods: HashMap<String, Rc<Closure>>,
+    /// Computed properties, run when the property is read.
+    pub getters: HashMap<String, Rc<Closure>>,
+    /// Run when the property is assigned, with the value as the argument.
+    pub setters: HashMap<String, Rc<Closure>>,
+    /// Methods called on the class itself, with `this` bound to the class.
+    pub static_methods: HashMap<String, Rc<Closure>>,
+    /// Fields declared with `var` in the class body, superclass ones first,
+    /// with methods computing their initial values.
+    pub field_initializers: Vec<(String, Rc<Closure>)>,
+    /// Class-level fields. Subclasses share them rather than getting copies.
+    pub static_fields: HashMap<String, Value>,
+    pub superclass: Option<Rc<RefCell<Class>>>,
+    /// Traits listed in the class's `with` clause, not those of superclasses.
+    pub traits: Vec<Rc<RefCell<Trait>>>,
+    /// Methods declared `abstract` here, in a superclass or in a trait. The
+    /// class can't be instantiated until each has an implementation.
+    pub abstract_methods: BTreeSet<String>,
 }
 
 impl Class {
@@ -13,14 +52,149 @@ impl Class {
         Self {
             name,
             methods: HashMap::new(),
+            getters: HashMap::new(),
+            setters: HashMap::new(),
+            static_methods: HashMap::new(),
+            field_initializers: Vec::new(),
+            static_fields: HashMap::new(),
+            superclass: None,
+            traits: Vec::new(),
+            abstract_methods: BTreeSet::new(),
+        }
+    }
+
+    /// Abstract methods without an implementation, in alphabetical order. A
+    /// getter counts as an implementation too.
+    pub fn missing_methods(&self) -> Vec<&str> {
+        self.abstract_methods
+            .iter()
+            .filter(|name| !self.methods.contains_key(*name) && !self.getters.contains_key(*name))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Whether this class or one of its superclasses, or a trait either of
+    /// them uses, is called `name`. Private members declared there are
+    /// accessible on instances of this class.
+    pub fn declares_privates_of(&self, name: &str) -> bool {
+        let matches = |class: &Class| {
+            class.name == name || class.traits.iter().any(|used| used.borrow().name == name)
+        };
+        if matches(self) {
+            return true;
+        }
+
+        let mut current = self.superclass.clone();
+        while let Some(class) = current {
+            if matches(&class.borrow()) {
+                return true;
+            }
+            current = class.borrow().superclass.clone();
+        }
+
+        false
+    }
+
+    /// Whether this class or one of its superclasses uses `trait`.
+    pub fn implements(&self, r#trait: &Rc<RefCell<Trait>>) -> bool {
+        let uses = |class: &Class| class.traits.iter().any(|used| Rc::ptr_eq(used, r#trait));
+        if uses(self) {
+            return true;
+        }
+
+        let mut current = self.superclass.clone();
+        while let Some(class) = current {
+            if uses(&class.borrow()) {
+                return true;
+            }
+            current = class.borrow().superclass.clone();
+        }
+
+        false
+    }
+
+    /// The class holding the class-level field `name` as seen from `class`:
+    /// the nearest one in its superclass chain that declares it.
+    pub fn static_field_owner(
+        class: &Rc<RefCell<Class>>,
+        name: &str,
+    ) -> Option<Rc<RefCell<Class>>> {
+        let mut current = Some(class.clone());
+        while let Some(candidate) = current {
+            if candidate.borrow().static_fields.contains_key(name) {
+                return Some(candidate);
+            }
+            current = candidate.borrow().superclass.clone();
+        }
+
+        None
+    }
+
+    /// Reads the class-level field `name` from `class` or its superclasses.
+    pub fn static_field(class: &Rc<RefCell<Class>>, name: &str) -> Option<Value> {
+        let owner = Class::static_field_owner(class, name)?;
+        let value = owner.borrow().static_fields.get(name).cloned();
+        value
+    }
+
+    /// Whether this class is `ancestor` or inherits from it.
+    pub fn is_subclass_of(&self, ancestor: &Rc<RefCell<Class>>) -> bool {
+        if std::ptr::eq(self, ancestor.as_ptr()) {
+            return true;
+        }
+
+        let mut current = self.superclass.clone();
+        while let Some(class) = current {
+            if Rc::ptr_eq(&class, ancestor) {
+                return true;
+            }
+            current = class.borrow().superclass.clone();
+        }
+
+        false
+    }
+}
+
+/// A set of methods declared with `trait`, copied into every class that
+/// lists it after `with`.
+#[derive(Debug, Clone)]
+pub struct Trait {
+    pub name: String,
+    pub methods: HashMap<String, Rc<Closure>>,
+    pub getters: HashMap<String, Rc<Closure>>,
+    pub setters: HashMap<String, Rc<Closure>>,
+    /// Methods the class using the trait has to implement.
+    pub abstract_methods: BTreeSet<String>,
+}
+
+impl Trait {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            methods: HashMap::new(),
+            getters: HashMap::new(),
+            setters: HashMap::new(),
+            abstract_methods: BTreeSet::new(),
         }
     }
+
+    /// Names of all the members the trait provides.
+    pub fn member_names(&self) -> impl Iterator<Item = &String> {
+        self.methods
+            .keys()
+            .chain(self.getters.keys())
+            .chain(self.setters.keys())
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Instance {
     pub class: Rc<RefCell<Class>>,
+    /// Written through `set_field` and `remove_field`, which keep
+    /// `field_order` in step.
     pub fields: HashMap<String, Value>,
+    /// Field names in the order they were first assigned.
+    pub field_order: Vec<String>,
 }
 
 impl Instance {
@@ -28,18 +202,33 @@ impl Instance {
         Self {
             class,
             fields: HashMap::new(),
+            field_order: Vec::new(),
+        }
+    }
+
+    pub fn set_field(&mut self, name: String, value: Value) {
+        if !self.fields.contains_key(&name) {
+            self.field_order.push(name.clone());
         }
+        self.fields.insert(name, value);
+    }
+
+    pub fn remove_field(&mut self, name: &str) -> Option<Value> {
+        let value = self.fields.remove(name)?;
+        self.field_order.retain(|field| field != name);
+        Some(value)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct BoundMethod {
     pub method: Rc<Closure>,
-    pub receiver: Rc<RefCell<Instance>>,
+    /// The instance, or the class for a static method.
+    pub receiver: Value,
 }
 
 impl BoundMethod {
-    pub fn new(method: Rc<Closure>, receiver: Rc<RefCell<Instance>>) -> Self {
+    pub fn new(method: Rc<Closure>, receiver: Value) -> Self {
         Self { method, receiver }
     }
 }
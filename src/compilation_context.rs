@@ -5,6 +5,7 @@ pub struct Variable {
     pub name: String,
     pub depth: Option<usize>,
     pub is_captured: bool,
+    pub is_const: bool,
 }
 
 impl Clone for Variable {
@@ -13,6 +14,7 @@ impl Clone for Variable {
             name: self.name.clone(),
             depth: self.depth,
             is_captured: self.is_captured,
+            is_const: self.is_const,
         }
     }
 }
@@ -21,6 +23,7 @@ impl Clone for Variable {
 pub struct Upvalue {
     pub index: usize,
     pub is_local: bool,
+    pub is_const: bool,
 }
 
 #[derive(Debug, Default)]
@@ -50,12 +53,19 @@ impl CompilationContext {
     }
 
     pub fn add_local(&mut self, name: String) -> Result<(), String> {
-        let depth = if name == "this" { Some(0) } else { None };
+        // The reserved zero slot ("this" in methods, unnamed otherwise) is
+        // always initialized and outlives every block scope in the function.
+        let depth = if name == "this" || name.is_empty() {
+            Some(0)
+        } else {
+            None
+        };
 
         self.variables.push(Variable {
             name,
             depth,
             is_captured: false,
+            is_const: false,
         });
         Ok(())
     }
@@ -73,6 +83,39 @@ impl CompilationContext {
         Ok(())
     }
 
+    /// Marks the `count` most recently declared variables initialized, for
+    /// declarations that introduce several at once.
+    pub fn mark_last_initialized(&mut self, count: usize) {
+        if self.depth == 0 {
+            return;
+        }
+
+        let start = self.variables.len().saturating_sub(count);
+        for variable in &mut self.variables[start..] {
+            variable.depth = Some(self.depth);
+        }
+    }
+
+    /// Makes the `count` most recently declared variables constants.
+    pub fn mark_const(&mut self, count: usize) {
+        let start = self.variables.len().saturating_sub(count);
+        for variable in &mut self.variables[start..] {
+            variable.is_const = true;
+        }
+    }
+
+    pub fn is_const_local(&self, index: usize) -> bool {
+        self.variables
+            .get(index)
+            .is_some_and(|variable| variable.is_const)
+    }
+
+    pub fn is_const_upvalue(&self, index: usize) -> bool {
+        self.upvalues
+            .get(index)
+            .is_some_and(|upvalue| upvalue.is_const)
+    }
+
     pub fn resolve_local(&self, name: &str) -> Result<Option<usize>, String> {
         let length = self.variables.len();
 
@@ -97,7 +140,7 @@ impl CompilationContext {
             return Ok(None);
         }
 
-        let (local_result, upvalue_result) = {
+        let (local_result, upvalue_result, is_const) = {
             let enclosing = match self.enclosing.as_mut() {
                 Some(enclosing) => enclosing,
                 None => return Ok(None),
@@ -108,20 +151,24 @@ impl CompilationContext {
             } else {
                 None
             };
-            (local, upvalue)
+            let is_const = match (local, upvalue) {
+                (Some(local), _) => enclosing.is_const_local(local),
+                (None, Some(upvalue)) => enclosing.is_const_upvalue(upvalue),
+                (None, None) => false,
+            };
+            (local, upvalue, is_const)
         };
 
         if let Some(local) = local_result {
-            self.add_upvalue(local, true)?;
+            let index = self.add_upvalue(local, true, is_const)?;
             if let Some(enclosing) = self.enclosing.as_mut() {
                 enclosing.capture(local);
             }
-            return Ok(Some(self.upvalues.len() - 1));
+            return Ok(Some(index));
         }
 
         if let Some(upvalue) = upvalue_result {
-            self.add_upvalue(upvalue, false)?;
-            return Ok(Some(self.upvalues.len() - 1));
+            return Ok(Some(self.add_upvalue(upvalue, false, is_const)?));
         }
 
         Ok(None)
@@ -157,21 +204,34 @@ impl CompilationContext {
         self.enclosing.take().map(|boxed| *boxed)
     }
 
-    fn add_upvalue(&mut self, index: usize, is_local: bool) -> Result<(), String> {
-        // compatibility with clox
-        if self.upvalues.len() > u8::MAX as usize {
-            return Err("Too many closure variables in function.".to_string());
-        }
-
+    /// Returns the position of the upvalue, reusing an existing one that
+    /// refers to the same variable.
+    fn add_upvalue(
+        &mut self,
+        index: usize,
+        is_local: bool,
+        is_const: bool,
+    ) -> Result<usize, String> {
         let existing_upvalue = self
             .upvalues
             .iter()
-            .find(|upvalue| upvalue.index == index && upvalue.is_local == is_local);
+            .position(|upvalue| upvalue.index == index && upvalue.is_local == is_local);
 
-        if existing_upvalue.is_none() {
-            self.upvalues.push(Upvalue { index, is_local });
+        if let Some(position) = existing_upvalue {
+            return Ok(position);
         }
 
-        Ok(())
+        // compatibility with clox
+        if self.upvalues.len() > u8::MAX as usize {
+            return Err("Too many closure variables in function.".to_string());
+        }
+
+        self.upvalues.push(Upvalue {
+            index,
+            is_local,
+            is_const,
+        });
+
+        Ok(self.upvalues.len() - 1)
     }
 }
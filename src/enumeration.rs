@@ -0,0 +1,104 @@
+use std::rc::Rc;
+
+use crate::value::Value;
+
+/// The name and variants of an `enum` declaration, shared by every variant
+/// value so they can tell which enum they belong to.
+#[derive(Debug)]
+pub struct EnumDefinition {
+    pub name: String,
+    pub variants: Vec<VariantDefinition>,
+}
+
+#[derive(Debug)]
+pub struct VariantDefinition {
+    pub name: String,
+    /// Names of the associated values; empty for a plain variant.
+    pub fields: Vec<String>,
+}
+
+/// An enum as a runtime value. Plain variants are created once, so every
+/// `Color.Red` is the same object.
+#[derive(Debug)]
+pub struct Enum {
+    pub definition: Rc<EnumDefinition>,
+    /// One value per variant, in declaration order: the variant itself, or
+    /// its constructor when it carries associated values.
+    pub variants: Rc<Vec<Value>>,
+}
+
+impl Enum {
+    pub fn new(definition: EnumDefinition) -> Self {
+        let definition = Rc::new(definition);
+        let variants = definition
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(index, variant)| {
+                let values = variant.fields.is_empty().then(Vec::new);
+                Value::Variant(Rc::new(Variant::new(definition.clone(), index, values)))
+            })
+            .collect();
+
+        Self {
+            definition,
+            variants: Rc::new(variants),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.definition.name
+    }
+
+    /// Looks a variant up by name.
+    pub fn variant(&self, name: &str) -> Option<Value> {
+        self.definition
+            .variants
+            .iter()
+            .position(|variant| variant.name == name)
+            .map(|index| self.variants[index].clone())
+    }
+}
+
+#[derive(Debug)]
+pub struct Variant {
+    pub definition: Rc<EnumDefinition>,
+    pub index: usize,
+    /// The associated values, or `None` for the constructor of a variant
+    /// that has not been given them yet.
+    pub values: Option<Vec<Value>>,
+}
+
+impl Variant {
+    pub fn new(definition: Rc<EnumDefinition>, index: usize, values: Option<Vec<Value>>) -> Self {
+        Self {
+            definition,
+            index,
+            values,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.definition.variants[self.index].name
+    }
+
+    pub fn fields(&self) -> &[String] {
+        &self.definition.variants[self.index].fields
+    }
+
+    pub fn is_constructor(&self) -> bool {
+        self.values.is_none()
+    }
+
+    /// Whether both are the same variant of the same enum, regardless of
+    /// their associated values.
+    pub fn same_variant(&self, other: &Variant) -> bool {
+        Rc::ptr_eq(&self.definition, &other.definition) && self.index == other.index
+    }
+
+    /// The associated value stored under `field`.
+    pub fn get(&self, field: &str) -> Option<Value> {
+        let position = self.fields().iter().position(|name| name == field)?;
+        self.values.as_ref()?.get(position).cloned()
+    }
+}
@@ -0,0 +1,42 @@
+/// The built-in error classes. Every kind except `Error` itself is a subclass
+/// of `Error`, declared in the prelude the VM runs on startup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    Error,
+    TypeError,
+    NameError,
+    PropertyError,
+    ArityError,
+    IndexError,
+    ArithmeticError,
+    FiberError,
+    MatchError,
+}
+
+impl ErrorKind {
+    pub const ALL: [ErrorKind; 9] = [
+        ErrorKind::Error,
+        ErrorKind::TypeError,
+        ErrorKind::NameError,
+        ErrorKind::PropertyError,
+        ErrorKind::ArityError,
+        ErrorKind::IndexError,
+        ErrorKind::ArithmeticError,
+        ErrorKind::FiberError,
+        ErrorKind::MatchError,
+    ];
+
+    pub fn class_name(&self) -> &'static str {
+        match self {
+            ErrorKind::Error => "Error",
+            ErrorKind::TypeError => "TypeError",
+            ErrorKind::NameError => "NameError",
+            ErrorKind::PropertyError => "PropertyError",
+            ErrorKind::ArityError => "ArityError",
+            ErrorKind::IndexError => "IndexError",
+            ErrorKind::ArithmeticError => "ArithmeticError",
+            ErrorKind::FiberError => "FiberError",
+            ErrorKind::MatchError => "MatchError",
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::rc::Rc;
+
+use crate::{
+    call_frame::CallFrame, closure::Closure, upvalue::Upvalue, value::Value, vm::CallFrameStack,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FiberState {
+    New,
+    Suspended,
+    Running,
+    Done,
+}
+
+/// A cooperatively scheduled coroutine with its own value and call frame stacks.
+///
+/// While a fiber is running, its context lives in the VM and these fields hold
+/// the context of whoever resumed it. Yielding or finishing swaps them back.
+#[derive(Debug)]
+pub struct Fiber {
+    pub closure: Rc<Closure>,
+    pub stack: Vec<Value>,
+    pub call_frame_stack: CallFrameStack,
+    pub open_upvalues: Vec<Rc<Upvalue>>,
+    pub state: FiberState,
+    /// Re-entrant calls in progress when the fiber was last resumed.
+    pub reentrant_calls: usize,
+    /// Set when resumed with `try`: a runtime error inside the fiber finishes
+    /// it and hands the error value to the resumer instead of aborting.
+    pub catches_errors: bool,
+}
+
+impl Fiber {
+    pub fn new(closure: Rc<Closure>) -> Self {
+        let frame = CallFrame {
+            closure: Rc::clone(&closure),
+            ip: 0,
+            slot_start: 0,
+        };
+
+        Self {
+            stack: vec![Value::Closure(Rc::clone(&closure))],
+            call_frame_stack: vec![frame],
+            open_upvalues: Vec::new(),
+            closure,
+            state: FiberState::New,
+            reentrant_calls: 0,
+            catches_errors: false,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.state == FiberState::Done
+    }
+}
@@ -1,19 +1,72 @@
 use std::fmt;
 
-use crate::{chunk::Chunk, value::Value};
+use crate::{chunk::Chunk, error::ErrorKind, value::Value, vm::VM};
 
 #[derive(Debug, Clone)]
 pub enum FunctionType {
     Function,
+    Lambda,
     Method,
     Initializer,
+    /// A computed property, run by reading `obj.name`.
+    Getter,
+    /// Run by assigning `obj.name = value`; the assigned value is its result.
+    Setter,
     Script,
 }
 
+/// The argument counts a function accepts: `required` arguments, up to
+/// `optional` more that have defaults, and any number beyond those when it
+/// has a rest parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Arity {
+    pub required: usize,
+    pub optional: usize,
+    pub rest: bool,
+}
+
+impl Arity {
+    pub fn exact(count: usize) -> Arity {
+        Arity {
+            required: count,
+            optional: 0,
+            rest: false,
+        }
+    }
+
+    pub fn at_least(count: usize) -> Arity {
+        Arity {
+            required: count,
+            optional: 0,
+            rest: true,
+        }
+    }
+
+    pub fn accepts(&self, count: usize) -> bool {
+        count >= self.required && (self.rest || count <= self.required + self.optional)
+    }
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.rest {
+            write!(f, "at least {}", self.required)
+        } else if self.optional > 0 {
+            write!(f, "{} to {}", self.required, self.required + self.optional)
+        } else {
+            write!(f, "{}", self.required)
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Function {
     pub name: String,
-    pub arity: usize,
+    pub arity: Arity,
+    /// Where execution starts, indexed by how many optional arguments the
+    /// call supplied. Earlier entries run the remaining defaults first; the
+    /// last one is the start of the body.
+    pub entry_points: Vec<usize>,
     pub function_type: FunctionType,
     pub chunk: Chunk,
 }
@@ -22,7 +75,8 @@ impl Function {
     pub fn new(name: String, arity: usize, function_type: FunctionType) -> Function {
         Function {
             name,
-            arity,
+            arity: Arity::exact(arity),
+            entry_points: vec![0],
             function_type,
             chunk: Chunk::new(),
         }
@@ -32,26 +86,35 @@ impl Function {
 impl fmt::Display for Function {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.function_type {
-            FunctionType::Function => write!(f, "fn {}", self.name),
+            FunctionType::Function | FunctionType::Lambda => write!(f, "fn {}", self.name),
             FunctionType::Method => write!(f, "method {}", self.name),
             FunctionType::Initializer => write!(f, "initializer {}", self.name),
+            FunctionType::Getter => write!(f, "getter {}", self.name),
+            FunctionType::Setter => write!(f, "setter {}", self.name),
             FunctionType::Script => write!(f, "<script>"),
         }
     }
 }
 
-pub type NativeFn = fn(args: Vec<Value>) -> Value;
+/// The kind and message of the runtime error a native function raises.
+pub type NativeError = (ErrorKind, String);
+
+/// A host function callable from Lox. It receives the VM so it can build
+/// values such as errors; returning `Err` raises a runtime error.
+pub type NativeFn = fn(vm: &mut VM, args: Vec<Value>) -> Result<Value, NativeError>;
 
 #[derive(Clone, Debug)]
 pub struct NativeFunction {
     pub name: String,
+    pub arity: Arity,
     pub function: NativeFn,
 }
 
 impl NativeFunction {
-    pub fn new(name: &str, function: NativeFn) -> NativeFunction {
+    pub fn new(name: &str, arity: Arity, function: NativeFn) -> NativeFunction {
         NativeFunction {
             name: name.to_string(),
+            arity,
             function,
         }
     }
@@ -0,0 +1,70 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{map::Map, range::Range, value::Value};
+
+#[derive(Debug, Clone)]
+pub enum IteratorSource {
+    List(Rc<RefCell<Vec<Value>>>),
+    Map(Rc<RefCell<Map>>),
+    Tuple(Rc<Vec<Value>>),
+    String(Rc<String>),
+    Range(Rc<Range>),
+}
+
+/// Cursor over a built-in iterable, produced by `GetIterator` for `for-in` loops.
+#[derive(Debug, Clone)]
+pub struct NativeIterator {
+    source: IteratorSource,
+    position: usize,
+}
+
+impl NativeIterator {
+    pub fn new(source: IteratorSource) -> Self {
+        Self {
+            source,
+            position: 0,
+        }
+    }
+
+    /// Returns the next element, or `None` once the source is exhausted.
+    ///
+    /// Lists are read live, so elements pushed during iteration are visited.
+    /// Maps yield their keys in insertion order, also read live.
+    /// Strings yield one character at a time; `position` is a byte offset.
+    /// Ranges yield their numbers; `position` counts the ones yielded.
+    pub fn advance(&mut self) -> Option<Value> {
+        match &self.source {
+            IteratorSource::List(list) => {
+                let value = list.borrow().get(self.position).cloned();
+                if value.is_some() {
+                    self.position += 1;
+                }
+                value
+            }
+            IteratorSource::Map(map) => {
+                let key = map.borrow().key_at(self.position);
+                if key.is_some() {
+                    self.position += 1;
+                }
+                key
+            }
+            IteratorSource::Tuple(values) => {
+                let value = values.get(self.position).cloned();
+                if value.is_some() {
+                    self.position += 1;
+                }
+                value
+            }
+            IteratorSource::String(string) => {
+                let c = string[self.position..].chars().next()?;
+                self.position += c.len_utf8();
+                Some(Value::String(Rc::new(c.to_string())))
+            }
+            IteratorSource::Range(range) => {
+                let value = range.get(self.position)?;
+                self.position += 1;
+                Some(Value::Number(value))
+            }
+        }
+    }
+}
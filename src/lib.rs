@@ -3,10 +3,16 @@ pub mod chunk;
 pub mod class;
 pub mod closure;
 pub mod compilation_context;
+pub mod enumeration;
+pub mod error;
+pub mod fiber;
 pub mod function;
+pub mod iterator;
 pub mod logger;
+pub mod map;
 pub mod native_functions;
 pub mod parser;
+pub mod range;
 pub mod run;
 pub mod scanner;
 pub mod upvalue;
@@ -14,17 +14,11 @@ impl DebugLevel {
     }
 
     pub fn is_debug(&self) -> bool {
-        match self {
-            DebugLevel::Debug => true,
-            _ => false,
-        }
+        matches!(self, DebugLevel::Debug)
     }
 
     pub fn is_info(&self) -> bool {
-        match self {
-            DebugLevel::Debug | DebugLevel::Info => true,
-            _ => false,
-        }
+        matches!(self, DebugLevel::Debug | DebugLevel::Info)
     }
 }
 
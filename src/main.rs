@@ -3,10 +3,16 @@ mod chunk;
 pub mod class;
 mod closure;
 mod compilation_context;
+mod enumeration;
+mod error;
+mod fiber;
 mod function;
+mod iterator;
 mod logger;
+mod map;
 mod native_functions;
 mod parser;
+mod range;
 mod run;
 mod scanner;
 pub mod upvalue;
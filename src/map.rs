@@ -0,0 +1,66 @@
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{chunk::SwitchKey, value::Value};
+
+/// A map from keys to values, built by `[key: value]`, that remembers
+/// insertion order.
+///
+/// Keys are hashed the same way `match` looks up literals, so only nil,
+/// booleans, numbers and strings can be keys, and `0` and `-0` are the same
+/// key.
+#[derive(Debug, Default)]
+pub struct Map {
+    entries: Vec<(SwitchKey, Value)>,
+    positions: HashMap<SwitchKey, usize>,
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, key: &SwitchKey) -> Option<Value> {
+        let position = *self.positions.get(key)?;
+        Some(self.entries[position].1.clone())
+    }
+
+    /// Sets the value of `key`. A new key goes after the existing ones; an
+    /// existing key keeps its place.
+    pub fn insert(&mut self, key: SwitchKey, value: Value) {
+        match self.positions.get(&key) {
+            Some(&position) => self.entries[position].1 = value,
+            None => {
+                self.positions.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+            }
+        }
+    }
+
+    /// The key at `position` in insertion order, as a value.
+    pub fn key_at(&self, position: usize) -> Option<Value> {
+        self.entries.get(position).map(|(key, _)| key_value(key))
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (Value, &Value)> {
+        self.entries
+            .iter()
+            .map(|(key, value)| (key_value(key), value))
+    }
+}
+
+fn key_value(key: &SwitchKey) -> Value {
+    match key {
+        SwitchKey::Nil => Value::Nil,
+        SwitchKey::Bool(b) => Value::Bool(*b),
+        SwitchKey::Number(bits) => Value::Number(f64::from_bits(*bits)),
+        SwitchKey::String(s) => Value::String(Rc::clone(s)),
+    }
+}
@@ -1,10 +1,350 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::class::{member_name, split_private_key, Instance};
+use crate::error::ErrorKind;
+use crate::fiber::Fiber;
+use crate::function::NativeError;
 use crate::value::Value;
+use crate::vm::VM;
 
-pub fn clock(_args: Vec<Value>) -> Value {
-    Value::Number(
+pub fn clock(_vm: &mut VM, _args: Vec<Value>) -> Result<Value, NativeError> {
+    Ok(Value::Number(
         std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_secs_f64(),
-    )
+    ))
+}
+
+pub fn fiber(_vm: &mut VM, args: Vec<Value>) -> Result<Value, NativeError> {
+    match args.as_slice() {
+        [Value::Closure(closure)] if closure.function.arity.required <= 1 => {
+            Ok(Value::fiber(Fiber::new(Rc::clone(closure))))
+        }
+        [Value::Closure(closure)] => Err((
+            ErrorKind::ArityError,
+            format!(
+                "Fiber function must take at most 1 parameter, {} takes {}",
+                closure.function.name, closure.function.arity
+            ),
+        )),
+        [value] => Err((
+            ErrorKind::TypeError,
+            format!("Fiber expects a function, got {}", value.type_name()),
+        )),
+        _ => Err((
+            ErrorKind::ArityError,
+            format!("Expected 1 argument but got {}", args.len()),
+        )),
+    }
+}
+
+pub fn len(_vm: &mut VM, args: Vec<Value>) -> Result<Value, NativeError> {
+    match args.as_slice() {
+        [Value::List(list)] => Ok(Value::Number(list.borrow().len() as f64)),
+        [Value::Map(map)] => Ok(Value::Number(map.borrow().len() as f64)),
+        [Value::Tuple(values)] => Ok(Value::Number(values.len() as f64)),
+        [Value::String(string)] => Ok(Value::Number(string.chars().count() as f64)),
+        [Value::Enum(enumeration)] => Ok(Value::Number(enumeration.variants.len() as f64)),
+        [Value::Range(range)] if range.start.is_none() || range.end.is_none() => Err((
+            ErrorKind::TypeError,
+            format!("len expects a range with both bounds, got {}", range),
+        )),
+        [Value::Range(range)] => match range.count() {
+            Some(count) => Ok(Value::Number(count as f64)),
+            None => Err((
+                ErrorKind::ArithmeticError,
+                "Range has too many numbers to count".to_string(),
+            )),
+        },
+        [value] => Err((
+            ErrorKind::TypeError,
+            format!(
+                "len expects a list, map, tuple, string, enum or range, got {}",
+                value.type_name()
+            ),
+        )),
+        _ => Err((
+            ErrorKind::ArityError,
+            format!("Expected 1 argument but got {}", args.len()),
+        )),
+    }
+}
+
+/// Converts a value to a string the way interpolation does, calling
+/// `toString()` on instances whose class defines it.
+pub fn str(vm: &mut VM, args: Vec<Value>) -> Result<Value, NativeError> {
+    match args.as_slice() {
+        [value] => Ok(Value::String(vm.native_stringify(value.clone())?)),
+        _ => Err((
+            ErrorKind::ArityError,
+            format!("Expected 1 argument but got {}", args.len()),
+        )),
+    }
+}
+
+pub fn push(_vm: &mut VM, args: Vec<Value>) -> Result<Value, NativeError> {
+    match args.as_slice() {
+        [Value::List(list), values @ ..] => {
+            list.borrow_mut().extend(values.iter().cloned());
+            Ok(Value::Nil)
+        }
+        [value, ..] => Err((
+            ErrorKind::TypeError,
+            format!("push expects a list, got {}", value.type_name()),
+        )),
+        _ => Err((
+            ErrorKind::ArityError,
+            format!("Expected at least 2 arguments but got {}", args.len()),
+        )),
+    }
+}
+
+pub fn type_of(_vm: &mut VM, args: Vec<Value>) -> Result<Value, NativeError> {
+    match args.as_slice() {
+        [value] => Ok(Value::String(Rc::new(value.type_name().to_string()))),
+        _ => Err((
+            ErrorKind::ArityError,
+            format!("Expected 1 argument but got {}", args.len()),
+        )),
+    }
+}
+
+/// The class of an instance, or `nil` for any other value.
+pub fn class_of(_vm: &mut VM, args: Vec<Value>) -> Result<Value, NativeError> {
+    match args.as_slice() {
+        [Value::Instance(instance)] => Ok(Value::Class(Rc::clone(&instance.borrow().class))),
+        [_] => Ok(Value::Nil),
+        _ => Err((
+            ErrorKind::ArityError,
+            format!("Expected 1 argument but got {}", args.len()),
+        )),
+    }
+}
+
+/// Checks the arguments of the field natives: an instance, a public field
+/// name and `count` arguments in all.
+fn field_arguments<'a>(
+    native: &str,
+    args: &'a [Value],
+    count: usize,
+) -> Result<(&'a Rc<RefCell<Instance>>, &'a str), NativeError> {
+    if args.len() != count {
+        return Err((
+            ErrorKind::ArityError,
+            format!("Expected {} arguments but got {}", count, args.len()),
+        ));
+    }
+
+    match (&args[0], &args[1]) {
+        (Value::Instance(_), Value::String(name)) if split_private_key(name).is_some() => Err((
+            ErrorKind::PropertyError,
+            format!(
+                "{} can't access private member '{}'",
+                native,
+                member_name(name)
+            ),
+        )),
+        (Value::Instance(instance), Value::String(name)) => Ok((instance, name)),
+        (Value::Instance(_), name) => Err((
+            ErrorKind::TypeError,
+            format!("{} expects a field name, got {}", native, name.type_name()),
+        )),
+        (value, _) => Err((
+            ErrorKind::TypeError,
+            format!("{} expects an instance, got {}", native, value.type_name()),
+        )),
+    }
+}
+
+pub fn has_field(_vm: &mut VM, args: Vec<Value>) -> Result<Value, NativeError> {
+    let (instance, name) = field_arguments("hasField", &args, 2)?;
+    let has_field = instance.borrow().fields.contains_key(name);
+    Ok(Value::Bool(has_field))
+}
+
+pub fn get_field(_vm: &mut VM, args: Vec<Value>) -> Result<Value, NativeError> {
+    let (instance, name) = field_arguments("getField", &args, 2)?;
+    let value = instance.borrow().fields.get(name).cloned();
+    value.ok_or_else(|| {
+        (
+            ErrorKind::PropertyError,
+            format!("Undefined field '{}'", name),
+        )
+    })
+}
+
+/// Assigns a field directly, bypassing setters, and returns the value.
+pub fn set_field(_vm: &mut VM, args: Vec<Value>) -> Result<Value, NativeError> {
+    let (instance, name) = field_arguments("setField", &args, 3)?;
+    instance
+        .borrow_mut()
+        .set_field(name.to_string(), args[2].clone());
+    Ok(args[2].clone())
+}
+
+/// Removes a field, returning whether the instance had it.
+pub fn delete_field(_vm: &mut VM, args: Vec<Value>) -> Result<Value, NativeError> {
+    let (instance, name) = field_arguments("deleteField", &args, 2)?;
+    let removed = instance.borrow_mut().remove_field(name);
+    Ok(Value::Bool(removed.is_some()))
+}
+
+/// The names of an instance's public fields, in the order they were first
+/// assigned.
+pub fn fields(_vm: &mut VM, args: Vec<Value>) -> Result<Value, NativeError> {
+    match args.as_slice() {
+        [Value::Instance(instance)] => Ok(Value::list(
+            instance
+                .borrow()
+                .field_order
+                .iter()
+                .filter(|name| split_private_key(name).is_none())
+                .map(|name| Value::String(Rc::new(name.clone())))
+                .collect(),
+        )),
+        [value] => Err((
+            ErrorKind::TypeError,
+            format!("fields expects an instance, got {}", value.type_name()),
+        )),
+        _ => Err((
+            ErrorKind::ArityError,
+            format!("Expected 1 argument but got {}", args.len()),
+        )),
+    }
+}
+
+/// The names of a class's public instance methods, inherited ones included,
+/// sorted.
+pub fn methods(_vm: &mut VM, args: Vec<Value>) -> Result<Value, NativeError> {
+    match args.as_slice() {
+        [Value::Class(class)] => {
+            let mut names = class
+                .borrow()
+                .methods
+                .keys()
+                .filter(|name| split_private_key(name).is_none())
+                .cloned()
+                .collect::<Vec<_>>();
+            names.sort();
+            Ok(Value::list(
+                names
+                    .into_iter()
+                    .map(|name| Value::String(Rc::new(name)))
+                    .collect(),
+            ))
+        }
+        [value] => Err((
+            ErrorKind::TypeError,
+            format!("methods expects a class, got {}", value.type_name()),
+        )),
+        _ => Err((
+            ErrorKind::ArityError,
+            format!("Expected 1 argument but got {}", args.len()),
+        )),
+    }
+}
+
+pub fn superclass_of(_vm: &mut VM, args: Vec<Value>) -> Result<Value, NativeError> {
+    match args.as_slice() {
+        [Value::Class(class)] => Ok(class
+            .borrow()
+            .superclass
+            .clone()
+            .map_or(Value::Nil, Value::Class)),
+        [value] => Err((
+            ErrorKind::TypeError,
+            format!("superclassOf expects a class, got {}", value.type_name()),
+        )),
+        _ => Err((
+            ErrorKind::ArityError,
+            format!("Expected 1 argument but got {}", args.len()),
+        )),
+    }
+}
+
+/// Whether an instance or class uses a trait, directly or through a
+/// superclass.
+pub fn implements(_vm: &mut VM, args: Vec<Value>) -> Result<Value, NativeError> {
+    match args.as_slice() {
+        [Value::Instance(instance), Value::Trait(r#trait)] => Ok(Value::Bool(
+            instance.borrow().class.borrow().implements(r#trait),
+        )),
+        [Value::Class(class), Value::Trait(r#trait)] => {
+            Ok(Value::Bool(class.borrow().implements(r#trait)))
+        }
+        [_, Value::Trait(_)] => Ok(Value::Bool(false)),
+        [_, value] => Err((
+            ErrorKind::TypeError,
+            format!(
+                "implements expects a trait as its second argument, got {}",
+                value.type_name()
+            ),
+        )),
+        _ => Err((
+            ErrorKind::ArityError,
+            format!("Expected 2 arguments but got {}", args.len()),
+        )),
+    }
+}
+
+/// The number of arguments a function, method, native or class requires.
+pub fn arity(_vm: &mut VM, args: Vec<Value>) -> Result<Value, NativeError> {
+    let required = match args.as_slice() {
+        [Value::Closure(closure)] => closure.function.arity.required,
+        [Value::BoundMethod(bound_method)] => bound_method.borrow().method.function.arity.required,
+        [Value::NativeFunction(native)] => native.arity.required,
+        [Value::Class(class)] => class
+            .borrow()
+            .methods
+            .get("init")
+            .map_or(0, |init| init.function.arity.required),
+        [value] => {
+            return Err((
+                ErrorKind::TypeError,
+                format!(
+                    "arity expects a function or class, got {}",
+                    value.type_name()
+                ),
+            ))
+        }
+        _ => {
+            return Err((
+                ErrorKind::ArityError,
+                format!("Expected 1 argument but got {}", args.len()),
+            ))
+        }
+    };
+
+    Ok(Value::Number(required as f64))
+}
+
+/// The declared name of a function, method, native, class or enum.
+pub fn name(_vm: &mut VM, args: Vec<Value>) -> Result<Value, NativeError> {
+    let name = match args.as_slice() {
+        [Value::Closure(closure)] => closure.function.name.clone(),
+        [Value::BoundMethod(bound_method)] => bound_method.borrow().method.function.name.clone(),
+        [Value::NativeFunction(native)] => native.name.clone(),
+        [Value::Class(class)] => class.borrow().name.clone(),
+        [Value::Trait(r#trait)] => r#trait.borrow().name.clone(),
+        [Value::Enum(enumeration)] => enumeration.name().to_string(),
+        [value] => {
+            return Err((
+                ErrorKind::TypeError,
+                format!(
+                    "name expects a function, class, trait or enum, got {}",
+                    value.type_name()
+                ),
+            ))
+        }
+        _ => {
+            return Err((
+                ErrorKind::ArityError,
+                format!("Expected 1 argument but got {}", args.len()),
+            ))
+        }
+    };
+
+    Ok(Value::String(Rc::new(name)))
 }
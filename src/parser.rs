@@ -1,15 +1,22 @@
+use std::collections::{HashMap, HashSet};
 use std::mem;
+use std::ops::Range;
 use std::rc::Rc;
 use std::vec;
 
 use crate::{
-    chunk::{Chunk, Instruction, OpCode},
+    chunk::{Chunk, Instruction, OpCode, SwitchKey, SwitchTable},
+    class::{member_name, private_key},
     compilation_context::CompilationContext,
-    function::{Function, FunctionType},
-    scanner::{Scanner, Token, TokenType},
+    enumeration::{Enum, EnumDefinition, VariantDefinition},
+    function::{Arity, Function, FunctionType},
+    scanner::{unescape, Scanner, Token, TokenType},
     value::Value,
 };
 
+/// The methods every `record` gets.
+const RECORD_MEMBERS: [&str; 4] = ["init", "__eq__", "toString", "copyWith"];
+
 pub struct Parser<'a> {
     scanner: Scanner<'a>,
     pub current: Option<Token<'a>>,
@@ -18,8 +25,102 @@ pub struct Parser<'a> {
     panic_mode: bool,
     compilation_context: CompilationContext,
     function_types: Vec<FunctionType>,
+    loops: Vec<LoopContext>,
     in_class: bool,
     has_superclass: bool,
+    in_trait: bool,
+    /// The classes and traits being compiled, innermost last.
+    classes: Vec<ClassContext>,
+}
+
+/// A class or trait being compiled. Its name qualifies the private members
+/// used in its body, each of which must be declared or assigned somewhere in
+/// that body.
+struct ClassContext {
+    name: String,
+    /// Keys of the private members the body declares or assigns.
+    declared: HashSet<String>,
+    /// Private members the body refers to, with the name as written and the
+    /// line, checked against `declared` once the body is compiled.
+    used: Vec<(String, String, usize)>,
+}
+
+impl ClassContext {
+    fn new(name: String) -> Self {
+        ClassContext {
+            name,
+            declared: HashSet::new(),
+            used: Vec::new(),
+        }
+    }
+}
+
+/// A pattern of a `match` arm, tested against the matched value.
+enum Pattern {
+    Literal(Value),
+    /// `low..high`, or `low..<high` when not inclusive.
+    Range(f64, f64, bool),
+    /// `is Name`, matching instances of the class and its subclasses, or
+    /// variants of the enum.
+    Class(String, usize),
+    /// `Enum.Variant`, matching that variant whatever its associated values.
+    Variant(String, String, usize),
+}
+
+/// A compiled `match` arm. `binding` copies the matched value into the
+/// arm's local, if it names one, and `exit` discards it again.
+struct MatchArm {
+    binding: Vec<Instruction>,
+    /// Alternatives separated by `|`; empty for `_` and bare bindings.
+    patterns: Vec<Pattern>,
+    guard: Option<Vec<Instruction>>,
+    body: Vec<Instruction>,
+    /// The loop exits recorded while compiling `body`.
+    body_exits: Range<usize>,
+    exit: Vec<Instruction>,
+    line: usize,
+}
+
+impl MatchArm {
+    fn is_catch_all(&self) -> bool {
+        self.patterns.is_empty() && self.guard.is_none()
+    }
+
+    fn is_literal(&self) -> bool {
+        self.guard.is_none()
+            && !self.patterns.is_empty()
+            && self
+                .patterns
+                .iter()
+                .all(|pattern| matches!(pattern, Pattern::Literal(_)))
+    }
+}
+
+/// The compiled arguments of a call.
+enum Arguments {
+    /// Each argument pushed in order, `count` in all.
+    Fixed(Vec<Instruction>, usize),
+    /// A single list of all the arguments, built because one was spread with
+    /// `...`.
+    Spread(Vec<Instruction>),
+}
+
+/// A loop being compiled, as seen by `break` and `continue` in its body.
+struct LoopContext {
+    /// Scope depth enclosing the loop body; locals deeper than this are
+    /// discarded when leaving an iteration early.
+    depth: usize,
+    /// The `break` and `continue` jumps in the body so far, aimed once the
+    /// loop is complete.
+    exits: Vec<LoopExit>,
+}
+
+/// A `break` or `continue` jump. `index` is its position in the statement
+/// that holds it, kept up to date by `move_loop_exits` as that statement is
+/// placed inside enclosing ones.
+struct LoopExit {
+    index: usize,
+    is_break: bool,
 }
 
 impl<'a> Parser<'a> {
@@ -33,14 +134,24 @@ impl<'a> Parser<'a> {
             panic_mode: false,
             compilation_context: CompilationContext::new(None),
             function_types: Vec::new(),
+            loops: Vec::new(),
             in_class: false,
             has_superclass: false,
+            in_trait: false,
+            classes: Vec::new(),
         }
     }
 
     pub fn parse(&mut self) -> Result<Vec<Instruction>, String> {
         let mut operations = Vec::new();
 
+        // The first token is scanned in `new`, before errors can be reported.
+        if let Some(token) = self.current {
+            if token.token_type == TokenType::Error {
+                self.scanner_error(&token)?;
+            }
+        }
+
         self.function_types.push(FunctionType::Script);
 
         while !self.is_at_end() {
@@ -57,18 +168,29 @@ impl<'a> Parser<'a> {
     fn is_at_end(&self) -> bool {
         self.current
             .as_ref()
-            .map_or(true, |t| t.token_type == TokenType::Eof)
+            .is_none_or(|t| t.token_type == TokenType::Eof)
     }
 
     fn declaration(&mut self) -> Result<Vec<Instruction>, String> {
         let result;
 
         if self.match_token(TokenType::Class)? {
-            result = self.class_declaration();
-        } else if self.match_token(TokenType::Fun)? {
+            result = self.class_declaration(false);
+        } else if self.check_word("record") && self.check_next(TokenType::Identifier) {
+            // `record` is only a keyword before a record's name.
+            self.advance()?;
+            result = self.class_declaration(true);
+        } else if self.match_token(TokenType::Enum)? {
+            result = self.enum_declaration();
+        } else if self.match_token(TokenType::Trait)? {
+            result = self.trait_declaration();
+        } else if self.check(TokenType::Fun) && !self.check_next(TokenType::LeftParen) {
+            self.advance()?;
             result = self.fun_declaration();
         } else if self.match_token(TokenType::Var)? {
             result = self.var_declaration();
+        } else if self.match_token(TokenType::Const)? {
+            result = self.const_declaration();
         } else {
             result = self.statement();
         }
@@ -80,22 +202,31 @@ impl<'a> Parser<'a> {
         result
     }
 
-    fn class_declaration(&mut self) -> Result<Vec<Instruction>, String> {
+    /// Compiles a class, or with `is_record` a `record Name(fields)`, whose
+    /// members are generated from the field list and whose body is optional.
+    fn class_declaration(&mut self, is_record: bool) -> Result<Vec<Instruction>, String> {
         self.in_class = true;
 
         let classname = self.parse_variable("Expect class name")?;
         let line = self.previous.ok_or("Unexpected end of input")?.line;
+        self.classes.push(ClassContext::new(classname.clone()));
 
         let mut result = vec![Instruction::new(OpCode::Class(classname.clone()), line)];
         let variable = self.define_variable(classname.clone(), line)?;
         result.extend(variable);
 
+        let record_fields = if is_record {
+            Some(self.record_fields()?)
+        } else {
+            None
+        };
+
         if self.match_token(TokenType::Less)? {
             let superclass = self.consume(TokenType::Identifier, "Expect superclass name.")?;
             let line = superclass.line;
             let superclassname = superclass.lexeme.to_string();
 
-            if superclass.lexeme == &classname {
+            if superclass.lexeme == classname {
                 return Err(self.format_error(
                     line,
                     &superclassname,
@@ -114,23 +245,343 @@ impl<'a> Parser<'a> {
 
             result.push(Instruction::new(OpCode::Inherit, line));
         }
+
+        let mut traits = Vec::new();
+        // `with` is only a keyword here, before the traits a class uses.
+        if self.check_word("with") {
+            self.advance()?;
+            loop {
+                let token = self.consume(TokenType::Identifier, "Expect trait name.")?;
+                traits.push((token.lexeme.to_string(), token.line));
+                if !self.match_token(TokenType::Comma)? {
+                    break;
+                }
+            }
+        }
+
         result.extend(self.named_variable(&classname, line, false)?);
+        if is_record {
+            // The class stays in a hidden local while its members are
+            // compiled, for the generated `__eq__` to capture.
+            self.begin_scope();
+            self.compilation_context
+                .add_local("record class".to_string())?;
+            self.compilation_context.mark_initialized()?;
+        }
 
-        self.consume(TokenType::LeftBrace, "Expect '{' before class body.")?;
+        let mut body = Vec::new();
+        let mut own_members = Vec::new();
+        // Generated members come first so the body can replace them.
+        if let Some(fields) = &record_fields {
+            body.extend(self.record_members(&classname, fields, line)?);
+            own_members.extend(RECORD_MEMBERS.iter().map(|name| name.to_string()));
+        }
 
-        while !self.check(TokenType::RightBrace) && !self.check(TokenType::Eof) {
-            result.extend(self.method()?);
+        if !is_record || !self.match_token(TokenType::Semicolon)? {
+            self.consume(TokenType::LeftBrace, "Expect '{' before class body.")?;
+
+            while !self.check(TokenType::RightBrace) && !self.check(TokenType::Eof) {
+                let token = self.current.ok_or("Unexpected end of input")?;
+                let is_modifier = ["static", "abstract", "var"]
+                    .into_iter()
+                    .any(|modifier| self.check_modifier(modifier));
+                if (token.token_type == TokenType::Identifier || token.token_type.is_keyword())
+                    && !is_modifier
+                {
+                    own_members.push(token.lexeme.to_string());
+                }
+                body.extend(self.method()?);
+            }
+
+            self.consume(TokenType::RightBrace, "Expect '}' after class body.")?;
         }
 
-        self.consume(TokenType::RightBrace, "Expect '}' after class body.")?;
+        // Trait members go in before the body so the class's own methods
+        // replace them, and after `Inherit` so they replace inherited ones.
+        if !traits.is_empty() {
+            for (name, line) in &traits {
+                result.extend(self.named_variable(name, *line, false)?);
+            }
+            result.push(Instruction::new(
+                OpCode::Implement(traits.len(), own_members),
+                line,
+            ));
+        }
+        result.extend(body);
 
-        result.push(Instruction::new(OpCode::Pop, line));
+        if is_record {
+            result.extend(self.end_scope()?);
+        } else {
+            result.push(Instruction::new(OpCode::Pop, line));
+        }
         if self.has_superclass {
             result.extend(self.end_scope()?);
         }
 
         self.in_class = false;
         self.has_superclass = false;
+        self.end_class()?;
+
+        Ok(result)
+    }
+
+    /// Parses the `(x, y)` field list of a record.
+    fn record_fields(&mut self) -> Result<Vec<String>, String> {
+        self.consume(TokenType::LeftParen, "Expect '(' after record name.")?;
+
+        let mut fields: Vec<String> = Vec::new();
+        if !self.check(TokenType::RightParen) {
+            loop {
+                self.consume(TokenType::Identifier, "Expect field name.")?;
+                let token = self.previous.ok_or("Unexpected end of input")?;
+                if fields.iter().any(|field| field == token.lexeme) {
+                    return Err(self.format_error(
+                        token.line,
+                        token.lexeme,
+                        "Duplicate field in record.",
+                    ));
+                }
+                fields.push(token.lexeme.to_string());
+
+                if !self.match_token(TokenType::Comma)? {
+                    break;
+                }
+            }
+        }
+
+        self.consume(TokenType::RightParen, "Expect ')' after record fields.")?;
+
+        Ok(fields)
+    }
+
+    /// Generates the members of a record: an initializer taking the fields
+    /// in order, structural `__eq__`, `toString` and `copyWith`, which
+    /// returns a copy with the fields named in its name and value pairs
+    /// replaced.
+    fn record_members(
+        &mut self,
+        class: &str,
+        fields: &[String],
+        line: usize,
+    ) -> Result<Vec<Instruction>, String> {
+        let mut operations = self.synthetic_method(
+            "init",
+            FunctionType::Initializer,
+            fields,
+            Arity::exact(fields.len()),
+            line,
+            |parser| {
+                let mut body = Vec::new();
+                for field in fields {
+                    body.extend(parser.named_variable("this", line, false)?);
+                    body.extend(parser.named_variable(field, line, false)?);
+                    body.push(Instruction::new(OpCode::SetProperty(field.clone()), line));
+                    body.push(Instruction::new(OpCode::Pop, line));
+                }
+                Ok(body)
+            },
+        )?;
+        operations.push(Instruction::new(OpCode::Method("init".to_string()), line));
+
+        let other = ["other".to_string()];
+        operations.extend(self.synthetic_method(
+            "__eq__",
+            FunctionType::Method,
+            &other,
+            Arity::exact(1),
+            line,
+            |parser| {
+                // other is Class and this.x == other.x and ...
+                let mut body = parser.named_variable("other", line, false)?;
+                body.extend(parser.named_variable("record class", line, false)?);
+                body.push(Instruction::new(OpCode::Is, line));
+                for field in fields {
+                    let mut term = parser.named_variable("this", line, false)?;
+                    term.push(Instruction::new(OpCode::GetProperty(field.clone()), line));
+                    term.extend(parser.named_variable("other", line, false)?);
+                    term.push(Instruction::new(OpCode::GetProperty(field.clone()), line));
+                    term.push(Instruction::new(OpCode::Equal, line));
+
+                    body.push(Instruction::new(OpCode::JumpIfFalse(term.len() + 1), line));
+                    body.push(Instruction::new(OpCode::Pop, line));
+                    body.extend(term);
+                }
+                body.push(Instruction::new(OpCode::Return, line));
+                Ok(body)
+            },
+        )?);
+        operations.push(Instruction::new(OpCode::Method("__eq__".to_string()), line));
+
+        operations.extend(self.synthetic_method(
+            "toString",
+            FunctionType::Method,
+            &[],
+            Arity::exact(0),
+            line,
+            |parser| {
+                // "Class(x=" + str(this.x) + ", y=" + ... + ")"
+                let string = |text: String| {
+                    Instruction::new(OpCode::Value(Value::String(Rc::new(text))), line)
+                };
+                let mut body = vec![string(format!("{}(", class))];
+                for (index, field) in fields.iter().enumerate() {
+                    let separator = if index == 0 { "" } else { ", " };
+                    body.push(string(format!("{}{}=", separator, field)));
+                    body.push(Instruction::new(OpCode::Add, line));
+                    body.extend(parser.named_variable("this", line, false)?);
+                    body.push(Instruction::new(OpCode::GetProperty(field.clone()), line));
+                    body.push(Instruction::new(OpCode::Stringify, line));
+                    body.push(Instruction::new(OpCode::Add, line));
+                }
+                body.push(string(")".to_string()));
+                body.push(Instruction::new(OpCode::Add, line));
+                body.push(Instruction::new(OpCode::Return, line));
+                Ok(body)
+            },
+        )?);
+        operations.push(Instruction::new(
+            OpCode::Method("toString".to_string()),
+            line,
+        ));
+
+        let changes = ["changes".to_string()];
+        operations.extend(self.synthetic_method(
+            "copyWith",
+            FunctionType::Method,
+            &changes,
+            Arity::at_least(0),
+            line,
+            |parser| {
+                let mut body = parser.named_variable("this", line, false)?;
+                body.extend(parser.named_variable("changes", line, false)?);
+                body.push(Instruction::new(OpCode::CopyWith, line));
+                body.push(Instruction::new(OpCode::Return, line));
+                Ok(body)
+            },
+        )?);
+        operations.push(Instruction::new(
+            OpCode::Method("copyWith".to_string()),
+            line,
+        ));
+
+        Ok(operations)
+    }
+
+    fn trait_declaration(&mut self) -> Result<Vec<Instruction>, String> {
+        self.in_class = true;
+        self.in_trait = true;
+
+        let name = self.parse_variable("Expect trait name")?;
+        let line = self.previous.ok_or("Unexpected end of input")?.line;
+        self.classes.push(ClassContext::new(name.clone()));
+
+        let mut result = vec![Instruction::new(OpCode::Trait(name.clone()), line)];
+        result.extend(self.define_variable(name.clone(), line)?);
+        result.extend(self.named_variable(&name, line, false)?);
+
+        self.consume(TokenType::LeftBrace, "Expect '{' before trait body.")?;
+
+        while !self.check(TokenType::RightBrace) && !self.check(TokenType::Eof) {
+            let token = self.current.ok_or("Unexpected end of input")?;
+            if self.check_modifier("static") {
+                return Err(self.format_error(
+                    token.line,
+                    token.lexeme,
+                    "A trait can't have static members.",
+                ));
+            }
+            if token.lexeme == "init" {
+                return Err(self.format_error(
+                    token.line,
+                    token.lexeme,
+                    "A trait can't define an initializer.",
+                ));
+            }
+            if self.check_modifier("var") {
+                return Err(self.format_error(
+                    token.line,
+                    token.lexeme,
+                    "A trait can't declare fields.",
+                ));
+            }
+            result.extend(self.method()?);
+        }
+
+        self.consume(TokenType::RightBrace, "Expect '}' after trait body.")?;
+        result.push(Instruction::new(OpCode::Pop, line));
+
+        self.in_class = false;
+        self.in_trait = false;
+        self.end_class()?;
+
+        Ok(result)
+    }
+
+    fn enum_declaration(&mut self) -> Result<Vec<Instruction>, String> {
+        let name = self.parse_variable("Expect enum name")?;
+        let line = self.previous.ok_or("Unexpected end of input")?.line;
+
+        self.consume(TokenType::LeftBrace, "Expect '{' before enum body.")?;
+
+        let mut variants: Vec<VariantDefinition> = Vec::new();
+        while !self.check(TokenType::RightBrace) && !self.check(TokenType::Eof) {
+            self.consume(TokenType::Identifier, "Expect variant name.")?;
+            let variant = self.previous.ok_or("Unexpected end of input")?;
+
+            if variants
+                .iter()
+                .any(|existing| existing.name == variant.lexeme)
+            {
+                return Err(self.format_error(
+                    variant.line,
+                    variant.lexeme,
+                    "Duplicate variant in enum.",
+                ));
+            }
+
+            let mut fields: Vec<String> = Vec::new();
+            if self.match_token(TokenType::LeftParen)? {
+                loop {
+                    self.consume(TokenType::Identifier, "Expect field name.")?;
+                    let field = self.previous.ok_or("Unexpected end of input")?;
+
+                    if fields.iter().any(|existing| existing == field.lexeme) {
+                        return Err(self.format_error(
+                            field.line,
+                            field.lexeme,
+                            "Duplicate field in variant.",
+                        ));
+                    }
+                    fields.push(field.lexeme.to_string());
+
+                    if !self.match_token(TokenType::Comma)? {
+                        break;
+                    }
+                }
+                self.consume(TokenType::RightParen, "Expect ')' after variant fields.")?;
+            }
+
+            variants.push(VariantDefinition {
+                name: variant.lexeme.to_string(),
+                fields,
+            });
+
+            if !self.match_token(TokenType::Comma)? {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RightBrace, "Expect '}' after enum body.")?;
+
+        let enumeration = Enum::new(EnumDefinition {
+            name: name.clone(),
+            variants,
+        });
+        let mut result = vec![Instruction::new(
+            OpCode::Value(Value::Enum(Rc::new(enumeration))),
+            line,
+        )];
+        result.extend(self.define_variable(name, line)?);
 
         Ok(result)
     }
@@ -174,53 +625,171 @@ impl<'a> Parser<'a> {
         name: String,
         function_type: FunctionType,
     ) -> Result<Vec<Instruction>, String> {
-        self.compilation_context =
-            CompilationContext::new(Some(Box::new(mem::take(&mut self.compilation_context))));
-
-        let zero_slot_name = match function_type {
-            FunctionType::Method => "this",
-            FunctionType::Initializer => "this",
-            _ => "",
-        };
+        let enclosing_loops = self.begin_function(&function_type)?;
 
-        self.compilation_context
-            .add_local(zero_slot_name.to_string())?;
-
-        self.consume(TokenType::LeftParen, "Expect '(' after function name")?;
-        let mut arity = 0;
+        // Getters have no parameter list at all.
+        let has_parameters = !matches!(function_type, FunctionType::Getter);
+        if has_parameters {
+            self.consume(TokenType::LeftParen, "Expect '(' after function name")?;
+        }
+        let mut arity = Arity::exact(0);
+        // Default values run in the callee, before the body, for every
+        // optional argument the call leaves out.
+        let mut defaults = Vec::new();
 
-        self.begin_scope();
         // Handle 0 arguments: only enter the loop if the next token is not ')'
-        if !self.check(TokenType::RightParen) {
+        if has_parameters && !self.check(TokenType::RightParen) {
             loop {
-                let param_token = self.consume(TokenType::Identifier, "Expect parameter name")?;
-                let param_name = param_token.lexeme.to_string();
+                let is_rest = self.match_token(TokenType::DotDotDot)?;
+                self.consume(TokenType::Identifier, "Expect parameter name")?;
+                let param_token = self.previous.ok_or("Unexpected end of input")?;
+
+                self.compilation_context
+                    .add_local(param_token.lexeme.to_string())?;
+
+                if is_rest {
+                    arity.rest = true;
+                } else if self.match_token(TokenType::Equal)? {
+                    defaults.push(self.expression()?);
+                    arity.optional += 1;
+                } else if arity.optional > 0 {
+                    return self.error_at(
+                        &param_token,
+                        "Parameter without a default can't follow one with a default.",
+                    );
+                } else {
+                    arity.required += 1;
+                }
 
-                self.compilation_context.add_local(param_name)?;
                 self.compilation_context.mark_initialized()?;
 
-                arity += 1;
-
-                if arity > 255 {
-                    return Err(format!("Cannot have more than 255 parameters"));
+                if arity.required + arity.optional + arity.rest as usize > 255 {
+                    return Err("Cannot have more than 255 parameters".to_string());
                 }
 
                 if !self.match_token(TokenType::Comma)? {
                     break;
                 }
+
+                if is_rest {
+                    return self.error_at(&param_token, "Rest parameter must be last.");
+                }
             }
         }
 
-        self.consume(TokenType::RightParen, "Expect ')' after parameters")?;
-        self.consume(TokenType::LeftBrace, "Expect '{' before function body")?;
+        if has_parameters {
+            self.consume(TokenType::RightParen, "Expect ')' after parameters")?;
+        }
+
+        if matches!(function_type, FunctionType::Setter) && arity != Arity::exact(1) {
+            let token = self.previous.ok_or("Unexpected end of input")?;
+            return self.error_at(&token, "A setter must have exactly one parameter.");
+        }
+
+        let block = if matches!(function_type, FunctionType::Lambda)
+            && self.match_token(TokenType::Arrow)?
+        {
+            let mut body = self.expression()?;
+            body.push(Instruction::new(OpCode::Return, self.get_line()?));
+            body
+        } else {
+            self.consume(TokenType::LeftBrace, "Expect '{' before function body")?;
+            self.block()?
+        };
+        let line = self.previous.ok_or("Unexpected end of input")?.line;
+
+        let operations = self.end_function(name, function_type, arity, defaults, block, line)?;
+        self.loops = enclosing_loops;
+
+        Ok(operations)
+    }
+
+    /// Compiles a method whose body the parser writes itself, with `this`
+    /// and `parameters` as its locals, for record members and field
+    /// initializers.
+    fn synthetic_method<F>(
+        &mut self,
+        name: &str,
+        function_type: FunctionType,
+        parameters: &[String],
+        arity: Arity,
+        line: usize,
+        body: F,
+    ) -> Result<Vec<Instruction>, String>
+    where
+        F: FnOnce(&mut Self) -> Result<Vec<Instruction>, String>,
+    {
+        let enclosing_loops = self.begin_function(&function_type)?;
+        for parameter in parameters {
+            self.compilation_context.add_local(parameter.clone())?;
+            self.compilation_context.mark_initialized()?;
+        }
+
+        let block = body(self)?;
+        let operations = self.end_function(
+            name.to_string(),
+            function_type,
+            arity,
+            Vec::new(),
+            block,
+            line,
+        )?;
+        self.loops = enclosing_loops;
+
+        Ok(operations)
+    }
+
+    /// Opens the compilation context of a function, with `this` or an
+    /// unnamed slot for the callee in local zero and a scope for the
+    /// parameters. Returns the loops of the enclosing function.
+    fn begin_function(&mut self, function_type: &FunctionType) -> Result<Vec<LoopContext>, String> {
+        self.compilation_context =
+            CompilationContext::new(Some(Box::new(mem::take(&mut self.compilation_context))));
+        let enclosing_loops = mem::take(&mut self.loops);
+
+        let zero_slot_name = match function_type {
+            FunctionType::Method
+            | FunctionType::Initializer
+            | FunctionType::Getter
+            | FunctionType::Setter => "this",
+            _ => "",
+        };
+
+        self.compilation_context
+            .add_local(zero_slot_name.to_string())?;
+        self.begin_scope();
+
+        Ok(enclosing_loops)
+    }
+
+    /// Closes the context opened by `begin_function` and emits the closure.
+    /// The caller restores the enclosing loops.
+    fn end_function(
+        &mut self,
+        name: String,
+        function_type: FunctionType,
+        arity: Arity,
+        defaults: Vec<Vec<Instruction>>,
+        block: Vec<Instruction>,
+        line: usize,
+    ) -> Result<Vec<Instruction>, String> {
+        let mut prologue = Vec::new();
+        let mut entry_points = Vec::new();
+        for default in defaults {
+            entry_points.push(prologue.len());
+            prologue.extend(default);
+        }
+        if arity.rest {
+            // Reached only when defaults ran, so no argument was left for it.
+            prologue.push(Instruction::new(OpCode::BuildList(0), line));
+        }
+        entry_points.push(prologue.len());
 
-        let block = self.block()?;
         let mut chunk = Chunk::new();
+        chunk.extend(prologue);
         chunk.extend(block);
         self.end_scope()?;
 
-        let line = self.previous.ok_or("Unexpected end of input")?.line;
-
         if matches!(function_type, FunctionType::Initializer) {
             chunk.extend(vec![
                 Instruction::new(OpCode::GetLocal(0), line),
@@ -228,6 +797,13 @@ impl<'a> Parser<'a> {
             ]);
         }
 
+        if matches!(function_type, FunctionType::Setter) {
+            chunk.extend(vec![
+                Instruction::new(OpCode::GetLocal(1), line),
+                Instruction::new(OpCode::Return, line),
+            ]);
+        }
+
         if !matches!(
             chunk.get_last_instruction().map(|i| &i.op_code),
             Some(OpCode::Return)
@@ -241,6 +817,7 @@ impl<'a> Parser<'a> {
         let function = Function {
             name: name.to_string(),
             arity,
+            entry_points,
             function_type,
             chunk,
         };
@@ -265,32 +842,299 @@ impl<'a> Parser<'a> {
         Ok(operations)
     }
 
+    /// Compiles an anonymous function expression: `fun (a, b) { ... }`, or
+    /// `fun (a) => expression` for a body that just returns a value.
+    fn lambda(&mut self) -> Result<Vec<Instruction>, String> {
+        let line = self.get_line()?;
+
+        self.function_types.push(FunctionType::Lambda);
+        let function = self.function(format!("<anonymous line {}>", line), FunctionType::Lambda);
+        self.function_types.pop();
+
+        function
+    }
+
     fn method(&mut self) -> Result<Vec<Instruction>, String> {
-        let token = self.consume(TokenType::Identifier, "Expect method name.")?;
-        let name = token.lexeme.to_string();
-        let line = token.line;
+        if self.check_modifier("static") {
+            self.advance()?;
+            return self.static_member();
+        }
 
-        let function_type = if name == "init" {
+        if self.check_modifier("abstract") {
+            self.advance()?;
+            return self.abstract_method();
+        }
+
+        if self.check_modifier("var") {
+            self.advance()?;
+            return self.field_declaration();
+        }
+
+        let name = self.member_name("Expect method name.")?;
+        let line = self.previous.ok_or("Unexpected end of input")?.line;
+        self.declare_member(&name);
+
+        let function_type = if self.check(TokenType::LeftBrace) {
+            FunctionType::Getter
+        } else if self.match_token(TokenType::Equal)? {
+            FunctionType::Setter
+        } else if name == "init" {
             FunctionType::Initializer
         } else {
             FunctionType::Method
         };
 
+        if name == "init" && !matches!(function_type, FunctionType::Initializer) {
+            return Err(self.format_error(
+                line,
+                &name,
+                "An initializer can't be a getter or setter.",
+            ));
+        }
+
         self.function_types.push(function_type.clone());
 
-        let mut operations = self.function(name.clone(), function_type)?;
+        let mut operations =
+            self.function(member_name(&name).to_string(), function_type.clone())?;
 
         self.function_types.pop();
 
-        operations.push(Instruction::new(OpCode::Method(name), line));
+        let op_code = match function_type {
+            FunctionType::Getter => OpCode::Getter(name),
+            FunctionType::Setter => OpCode::Setter(name),
+            _ => OpCode::Method(name),
+        };
+        operations.push(Instruction::new(op_code, line));
+
+        Ok(operations)
+    }
+
+    /// Compiles `var name = value;` in a class body once `var` is consumed.
+    /// The value is compiled as a method, so that it is computed afresh for
+    /// every new instance and can use `this`.
+    fn field_declaration(&mut self) -> Result<Vec<Instruction>, String> {
+        let name = self.member_name("Expect field name.")?;
+        let line = self.previous.ok_or("Unexpected end of input")?.line;
+        self.declare_member(&name);
+
+        let mut operations = self.synthetic_method(
+            member_name(&name),
+            FunctionType::Method,
+            &[],
+            Arity::exact(0),
+            line,
+            |parser| {
+                let mut body = if parser.match_token(TokenType::Equal)? {
+                    parser.expression()?
+                } else {
+                    vec![Instruction::new(OpCode::Value(Value::Nil), line)]
+                };
+                body.push(Instruction::new(OpCode::Return, line));
+                Ok(body)
+            },
+        )?;
+        self.consume(TokenType::Semicolon, "Expect ';' after field declaration.")?;
+        operations.push(Instruction::new(OpCode::Field(name), line));
+
+        Ok(operations)
+    }
+
+    /// Compiles `abstract name(...);` once `abstract` is consumed. The
+    /// parameter names only document what implementations should take.
+    fn abstract_method(&mut self) -> Result<Vec<Instruction>, String> {
+        let token = self.current.ok_or("Unexpected end of input")?;
+        if token.token_type == TokenType::PrivateName {
+            return Err(self.format_error(
+                token.line,
+                token.lexeme,
+                "An abstract method can't be private.",
+            ));
+        }
+
+        let name = self.identifier_or_keyword("Expect method name.")?;
+        let line = self.previous.ok_or("Unexpected end of input")?.line;
+
+        if name == "init" {
+            return Err(self.format_error(line, &name, "An initializer can't be abstract."));
+        }
+
+        self.consume(TokenType::LeftParen, "Expect '(' after method name.")?;
+        if !self.check(TokenType::RightParen) {
+            loop {
+                self.consume(TokenType::Identifier, "Expect parameter name.")?;
+                if !self.match_token(TokenType::Comma)? {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenType::RightParen, "Expect ')' after parameters.")?;
+        self.consume(TokenType::Semicolon, "Expect ';' after abstract method.")?;
+
+        Ok(vec![Instruction::new(OpCode::AbstractMethod(name), line)])
+    }
+
+    /// Compiles `static name(...) {...}` or a class-level field
+    /// `static name = value;` once `static` is consumed.
+    fn static_member(&mut self) -> Result<Vec<Instruction>, String> {
+        let name = self.member_name("Expect static member name.")?;
+        let line = self.previous.ok_or("Unexpected end of input")?.line;
+        self.declare_member(&name);
+
+        if !self.check(TokenType::LeftParen) {
+            // The initializer runs once, in the scope around the class, so
+            // there is no `this` or `super` to refer to.
+            let (in_class, has_superclass) = (self.in_class, self.has_superclass);
+            self.in_class = false;
+            self.has_superclass = false;
+            let initializer = if self.match_token(TokenType::Equal)? {
+                self.expression()
+            } else {
+                Ok(vec![Instruction::new(OpCode::Value(Value::Nil), line)])
+            };
+            self.in_class = in_class;
+            self.has_superclass = has_superclass;
+
+            let mut operations = initializer?;
+            self.consume(TokenType::Semicolon, "Expect ';' after static field.")?;
+            operations.push(Instruction::new(OpCode::StaticField(name), line));
+            return Ok(operations);
+        }
+
+        if name == "init" {
+            return Err(self.format_error(line, &name, "An initializer can't be static."));
+        }
+
+        self.function_types.push(FunctionType::Method);
+        let mut operations = self.function(member_name(&name).to_string(), FunctionType::Method)?;
+        self.function_types.pop();
+
+        operations.push(Instruction::new(OpCode::StaticMethod(name), line));
 
         Ok(operations)
     }
 
     fn var_declaration(&mut self) -> Result<Vec<Instruction>, String> {
+        if self.match_destructuring_pattern()? {
+            return self.destructuring_declaration(false);
+        }
+
         let name = self.parse_variable("Expect variable name")?;
         let line = self.previous.ok_or("Unexpected end of input")?.line;
 
+        self.var_initializer(name, line)
+    }
+
+    /// Compiles `const NAME = value;`. Assigning to a constant local or upvalue
+    /// is a compile error; constant globals are checked by the VM.
+    fn const_declaration(&mut self) -> Result<Vec<Instruction>, String> {
+        if self.match_destructuring_pattern()? {
+            return self.destructuring_declaration(true);
+        }
+
+        let name = self.parse_variable("Expect constant name")?;
+        let line = self.previous.ok_or("Unexpected end of input")?.line;
+
+        self.consume(TokenType::Equal, "Expect '=' after constant name")?;
+        let mut operations = self.expression()?;
+        self.consume(
+            TokenType::Semicolon,
+            "Expect ';' after constant declaration",
+        )?;
+
+        if self.compilation_context.get_depth() > 0 {
+            self.compilation_context.mark_initialized()?;
+            self.compilation_context.mark_const(1);
+        } else {
+            operations.push(Instruction::new(OpCode::DefineConstant(name), line));
+        }
+
+        Ok(operations)
+    }
+
+    fn match_destructuring_pattern(&mut self) -> Result<bool, String> {
+        Ok(self.match_token(TokenType::LeftParen)?
+            || self.match_token(TokenType::LeftBracket)?
+            || self.match_token(TokenType::LeftBrace)?)
+    }
+
+    /// Compiles the declarations `var (a, b) = value;`, `var [a, b] = value;`
+    /// and `var {x, y} = value;`, after the opening delimiter.
+    ///
+    /// The first two unpack a tuple or list with exactly as many elements, or
+    /// at least as many when the last name is a `...rest` list. Braces read
+    /// the named properties of the value instead.
+    fn destructuring_declaration(&mut self, is_const: bool) -> Result<Vec<Instruction>, String> {
+        let open = self.previous.ok_or("Unexpected end of input")?;
+        let line = open.line;
+        let (close, message) = match open.token_type {
+            TokenType::LeftParen => (TokenType::RightParen, "Expect ')' after names."),
+            TokenType::LeftBracket => (TokenType::RightBracket, "Expect ']' after names."),
+            _ => (TokenType::RightBrace, "Expect '}' after field names."),
+        };
+        let unpacks_fields = close == TokenType::RightBrace;
+
+        let mut names = Vec::new();
+        let mut rest = false;
+        loop {
+            if !unpacks_fields && self.match_token(TokenType::DotDotDot)? {
+                rest = true;
+            }
+            names.push(self.parse_variable("Expect variable name")?);
+
+            if !self.match_token(TokenType::Comma)? {
+                break;
+            }
+
+            if rest {
+                let token = self.previous.ok_or("Unexpected end of input")?;
+                return self.error_at(&token, "Rest element must be last.");
+            }
+        }
+
+        self.consume(close, message)?;
+        self.consume(TokenType::Equal, "Expect '=' after destructuring pattern.")?;
+        let mut operations = self.expression()?;
+        self.consume(
+            TokenType::Semicolon,
+            "Expect ';' after variable declaration",
+        )?;
+
+        if unpacks_fields {
+            // Each field is read from a copy and slid beneath the value, which
+            // is dropped at the end.
+            for name in &names {
+                operations.push(Instruction::new(OpCode::Dup(0), line));
+                operations.push(Instruction::new(OpCode::GetProperty(name.clone()), line));
+                operations.push(Instruction::new(OpCode::Bury(1), line));
+            }
+            operations.push(Instruction::new(OpCode::Pop, line));
+        } else {
+            let count = names.len() - rest as usize;
+            operations.push(Instruction::new(OpCode::Unpack(count, rest), line));
+        }
+
+        if self.compilation_context.get_depth() > 0 {
+            self.compilation_context.mark_last_initialized(names.len());
+            if is_const {
+                self.compilation_context.mark_const(names.len());
+            }
+        } else {
+            for name in names.into_iter().rev() {
+                let define = if is_const {
+                    OpCode::DefineConstant(name)
+                } else {
+                    OpCode::DefineGlobal(name)
+                };
+                operations.push(Instruction::new(define, line));
+            }
+        }
+
+        Ok(operations)
+    }
+
+    /// Parses the optional `= value` and trailing `;` of a variable declaration
+    /// whose name has already been declared.
+    fn var_initializer(&mut self, name: String, line: usize) -> Result<Vec<Instruction>, String> {
         let mut operations = Vec::new();
 
         let match_equal = self.match_token(TokenType::Equal)?;
@@ -349,17 +1193,15 @@ impl<'a> Parser<'a> {
     ///
     /// Returns `Ok(())` if the variable was successfully declared, or an error message otherwise.
     fn declare_variable(&mut self, name: String) -> Result<(), String> {
+        let depth = self.compilation_context.get_depth();
         for variable in self.compilation_context.iter() {
-            match variable.depth {
-                Some(depth)
-                    if depth == self.compilation_context.get_depth() && variable.name == name =>
-                {
-                    return Err(format!(
-                        "Variable '{}' already declared in this scope",
-                        name
-                    ));
-                }
-                _ => {}
+            // Variables still being declared have no depth yet, but are always
+            // in the current scope.
+            if variable.name == name && variable.depth.is_none_or(|d| d == depth) {
+                return Err(format!(
+                    "Variable '{}' already declared in this scope",
+                    name
+                ));
             }
         }
 
@@ -376,6 +1218,10 @@ impl<'a> Parser<'a> {
             return self.if_statement();
         }
 
+        if self.match_token(TokenType::Match)? {
+            return self.match_statement();
+        }
+
         if self.match_token(TokenType::Return)? {
             return self.return_statement();
         }
@@ -388,6 +1234,14 @@ impl<'a> Parser<'a> {
             return self.for_statement();
         }
 
+        if self.match_token(TokenType::Break)? {
+            return self.loop_exit_statement(TokenType::Break);
+        }
+
+        if self.match_token(TokenType::Continue)? {
+            return self.loop_exit_statement(TokenType::Continue);
+        }
+
         let mut operations = Vec::new();
 
         if self.match_token(TokenType::LeftBrace)? {
@@ -415,7 +1269,9 @@ impl<'a> Parser<'a> {
         let mut operations = self.expression()?;
         self.consume(TokenType::RightParen, "Expect ')' after if condition")?;
 
+        let first_exit = self.loop_exit_count();
         let mut then_statement = self.statement()?;
+        self.move_loop_exits(first_exit..self.loop_exit_count(), operations.len() + 2); // after JumpIfFalse + Pop
 
         let match_else = self.match_token(TokenType::Else)?;
 
@@ -427,7 +1283,9 @@ impl<'a> Parser<'a> {
         operations.append(&mut then_statement);
 
         if match_else {
+            let first_exit = self.loop_exit_count();
             let mut else_statement = self.statement()?;
+            self.move_loop_exits(first_exit..self.loop_exit_count(), operations.len() + 2); // after Jump + Pop
 
             operations.push(Instruction::new(
                 OpCode::Jump(else_statement.len() + 1), // + Pop
@@ -443,6 +1301,405 @@ impl<'a> Parser<'a> {
         Ok(operations)
     }
 
+    /// Compiles `match (value) { pattern => statement ... }`.
+    ///
+    /// The matched value lives in a hidden local for the whole statement. When
+    /// every arm but a final catch-all is a literal, the arms are dispatched
+    /// through a `Switch` jump table; otherwise each arm is tested in turn.
+    /// A value no arm matches is a runtime error.
+    fn match_statement(&mut self) -> Result<Vec<Instruction>, String> {
+        let line = self.get_line()?;
+        self.consume(TokenType::LeftParen, "Expect '(' after 'match'.")?;
+        self.begin_scope();
+        let mut operations = self.expression()?;
+        self.consume(TokenType::RightParen, "Expect ')' after match value.")?;
+        self.consume(TokenType::LeftBrace, "Expect '{' before match arms.")?;
+
+        self.compilation_context
+            .add_local("match value".to_string())?;
+        self.compilation_context.mark_initialized()?;
+        let slot = self
+            .compilation_context
+            .resolve_local("match value")?
+            .ok_or("Failed to resolve match value")?;
+
+        let first_exit = self.loop_exit_count();
+        let mut arms: Vec<MatchArm> = Vec::new();
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            if arms.last().is_some_and(MatchArm::is_catch_all) {
+                let token = self.current.ok_or("Unexpected end of input")?;
+                return self.error_at(&token, "Unreachable match arm after catch-all.");
+            }
+            arms.push(self.match_arm(slot)?);
+        }
+
+        self.consume(TokenType::RightBrace, "Expect '}' after match arms.")?;
+
+        let catch_all = match arms.last() {
+            Some(arm) if arm.is_catch_all() => arms.pop(),
+            _ => None,
+        };
+        let (fallback, fallback_exits) = match catch_all {
+            Some(arm) => {
+                let mut fallback = arm.binding;
+                self.move_loop_exits(arm.body_exits.clone(), fallback.len());
+                fallback.extend(arm.body);
+                fallback.extend(arm.exit);
+                (fallback, arm.body_exits)
+            }
+            None => (
+                vec![
+                    Instruction::new(OpCode::GetLocal(slot), line),
+                    Instruction::new(OpCode::NoMatch, line),
+                ],
+                0..0,
+            ),
+        };
+        let fallback_len = fallback.len();
+
+        let dispatch = if !arms.is_empty() && arms.iter().all(MatchArm::is_literal) {
+            self.match_table(slot, arms, fallback, line)
+        } else {
+            self.match_chain(slot, arms, fallback)?
+        };
+        // Both place the fallback last.
+        self.move_loop_exits(fallback_exits, dispatch.len() - fallback_len);
+        self.move_loop_exits(first_exit..self.loop_exit_count(), operations.len());
+        operations.extend(dispatch);
+
+        operations.extend(self.end_scope()?);
+        Ok(operations)
+    }
+
+    /// Parses one arm: its patterns or binding, an optional `if` guard and
+    /// the statement after `=>`. A binding is a local scoped to the arm.
+    fn match_arm(&mut self, slot: usize) -> Result<MatchArm, String> {
+        let line = self.current.ok_or("Unexpected end of input")?.line;
+        self.begin_scope();
+
+        let mut patterns = Vec::new();
+        let mut binding = Vec::new();
+
+        if !self.check_next(TokenType::Dot) && self.match_token(TokenType::Identifier)? {
+            let token = self.previous.ok_or("Unexpected end of input")?;
+
+            if token.lexeme != "_" {
+                binding.push(Instruction::new(OpCode::GetLocal(slot), token.line));
+                self.declare_variable(token.lexeme.to_string())?;
+                self.compilation_context.mark_initialized()?;
+            }
+
+            if self.match_token(TokenType::Is)? {
+                patterns.push(self.class_pattern()?);
+            }
+        } else {
+            loop {
+                patterns.push(self.pattern()?);
+
+                if !self.match_token(TokenType::Pipe)? {
+                    break;
+                }
+            }
+        }
+
+        let guard = if self.match_token(TokenType::If)? {
+            Some(self.expression()?)
+        } else {
+            None
+        };
+
+        self.consume(TokenType::Arrow, "Expect '=>' after match pattern.")?;
+        let first_exit = self.loop_exit_count();
+        let body = self.statement()?;
+        let body_exits = first_exit..self.loop_exit_count();
+        let exit = self.end_scope()?;
+
+        Ok(MatchArm {
+            binding,
+            patterns,
+            guard,
+            body,
+            body_exits,
+            exit,
+            line,
+        })
+    }
+
+    fn pattern(&mut self) -> Result<Pattern, String> {
+        if self.match_token(TokenType::Is)? {
+            return self.class_pattern();
+        }
+
+        if self.match_token(TokenType::Identifier)? {
+            return self.variant_pattern();
+        }
+
+        let value = self.pattern_literal()?;
+
+        let inclusive = if self.match_token(TokenType::DotDot)? {
+            true
+        } else if self.match_token(TokenType::DotDotLess)? {
+            false
+        } else {
+            return Ok(Pattern::Literal(value));
+        };
+
+        let high = self.pattern_literal()?;
+        match (value, high) {
+            (Value::Number(low), Value::Number(high)) => Ok(Pattern::Range(low, high, inclusive)),
+            _ => {
+                let token = self.previous.ok_or("Unexpected end of input")?;
+                Err(self.format_error(
+                    token.line,
+                    token.lexeme,
+                    "Range pattern bounds must be numbers.",
+                ))
+            }
+        }
+    }
+
+    fn class_pattern(&mut self) -> Result<Pattern, String> {
+        self.consume(TokenType::Identifier, "Expect class name after 'is'.")?;
+
+        if self.check(TokenType::Dot) {
+            return self.variant_pattern();
+        }
+
+        let class = self.previous.ok_or("Unexpected end of input")?;
+        Ok(Pattern::Class(class.lexeme.to_string(), class.line))
+    }
+
+    /// Parses the rest of `Enum.Variant` once the enum name is consumed.
+    fn variant_pattern(&mut self) -> Result<Pattern, String> {
+        let enumeration = self.previous.ok_or("Unexpected end of input")?;
+        self.consume(TokenType::Dot, "Expect '.' after enum name.")?;
+        let variant = self.consume(TokenType::Identifier, "Expect variant name after '.'.")?;
+
+        Ok(Pattern::Variant(
+            enumeration.lexeme.to_string(),
+            variant.lexeme.to_string(),
+            enumeration.line,
+        ))
+    }
+
+    /// Parses a number, string, boolean or `nil` literal, with an optional
+    /// leading `-` on numbers.
+    fn pattern_literal(&mut self) -> Result<Value, String> {
+        let negate = self.match_token(TokenType::Minus)?;
+        self.advance()?;
+        let token = self.previous.ok_or("Unexpected end of input")?;
+
+        let instructions = match token.token_type {
+            TokenType::Number => self.number()?,
+            TokenType::True
+            | TokenType::False
+            | TokenType::Nil
+            | TokenType::String
+            | TokenType::RawString
+                if !negate =>
+            {
+                self.literal()?
+            }
+            _ => {
+                return Err(self.format_error(token.line, token.lexeme, "Expect pattern."));
+            }
+        };
+
+        match instructions.into_iter().next().map(|i| i.op_code) {
+            Some(OpCode::Value(Value::Number(n))) if negate => Ok(Value::Number(-n)),
+            Some(OpCode::Value(value)) => Ok(value),
+            _ => Err(self.format_error(token.line, token.lexeme, "Expect pattern.")),
+        }
+    }
+
+    /// Dispatches literal arms through a `Switch`; unmatched values continue
+    /// at `fallback`. Earlier arms win when a literal repeats.
+    ///
+    /// An instance whose class overloads `==` can't be looked up, so the
+    /// `Switch` lets it fall through to a chain of `Equal` tests against the
+    /// literals in order, which jump to the same bodies.
+    fn match_table(
+        &mut self,
+        slot: usize,
+        arms: Vec<MatchArm>,
+        fallback: Vec<Instruction>,
+        line: usize,
+    ) -> Vec<Instruction> {
+        const TEST_LEN: usize = 7;
+        let literal_count = arms.iter().map(|arm| arm.patterns.len()).sum::<usize>();
+        let chain_len = literal_count * TEST_LEN + 1;
+
+        let mut cases = HashMap::new();
+        let mut literals = Vec::new();
+        let mut bodies: Vec<Instruction> = Vec::new();
+        let mut exits = Vec::new();
+
+        for arm in arms {
+            for pattern in arm.patterns {
+                if let Pattern::Literal(value) = pattern {
+                    if let Some(key) = SwitchKey::from_value(&value) {
+                        cases.entry(key).or_insert(chain_len + bodies.len());
+                    }
+                    literals.push((value, bodies.len(), arm.line));
+                }
+            }
+
+            bodies.extend(arm.binding);
+            // After GetLocal, Switch and the chain.
+            self.move_loop_exits(arm.body_exits, chain_len + bodies.len() + 2);
+            bodies.extend(arm.body);
+            bodies.extend(arm.exit);
+            exits.push(bodies.len());
+            bodies.push(Instruction::new(OpCode::Jump(0), line));
+        }
+
+        let mut chain = Vec::new();
+        for (value, body, line) in literals {
+            chain.extend([
+                Instruction::new(OpCode::GetLocal(slot), line),
+                Instruction::new(OpCode::Value(value), line),
+                Instruction::new(OpCode::Equal, line),
+                Instruction::new(OpCode::JumpIfFalse(2), line),
+                Instruction::new(OpCode::Pop, line),
+            ]);
+            let target = chain_len + body;
+            chain.push(Instruction::new(
+                OpCode::Jump(target - chain.len() - 1),
+                line,
+            ));
+            chain.push(Instruction::new(OpCode::Pop, line));
+        }
+        chain.push(Instruction::new(
+            OpCode::Jump(chain_len + bodies.len() - chain.len() - 1),
+            line,
+        ));
+
+        let table = SwitchTable {
+            cases,
+            default: chain_len + bodies.len(),
+        };
+        bodies.extend(fallback);
+
+        let end = bodies.len();
+        for index in exits {
+            bodies[index].op_code = OpCode::Jump(end - index - 1);
+        }
+
+        let mut operations = vec![
+            Instruction::new(OpCode::GetLocal(slot), line),
+            Instruction::new(OpCode::Switch(Rc::new(table)), line),
+        ];
+        operations.extend(chain);
+        operations.extend(bodies);
+        operations
+    }
+
+    /// Tests the arms one after another, running the first that matches and
+    /// then `fallback` if none does.
+    fn match_chain(
+        &mut self,
+        slot: usize,
+        arms: Vec<MatchArm>,
+        fallback: Vec<Instruction>,
+    ) -> Result<Vec<Instruction>, String> {
+        let mut operations = Vec::new();
+        let mut exits = Vec::new();
+
+        for arm in arms {
+            let line = arm.line;
+            let mut condition: Option<Vec<Instruction>> = None;
+            for pattern in arm.patterns {
+                let mut test = self.pattern_test(slot, pattern, line)?;
+                condition = Some(match condition {
+                    None => test,
+                    Some(mut alternatives) => {
+                        // `alternatives or test`
+                        alternatives.push(Instruction::new(OpCode::JumpIfFalse(1), line));
+                        alternatives.push(Instruction::new(OpCode::Jump(test.len() + 1), line));
+                        alternatives.push(Instruction::new(OpCode::Pop, line));
+                        alternatives.append(&mut test);
+                        alternatives
+                    }
+                });
+            }
+
+            if let Some(mut guard) = arm.guard {
+                condition = Some(match condition {
+                    None => guard,
+                    Some(mut condition) => {
+                        // `condition and guard`
+                        condition
+                            .push(Instruction::new(OpCode::JumpIfFalse(guard.len() + 1), line));
+                        condition.push(Instruction::new(OpCode::Pop, line));
+                        condition.append(&mut guard);
+                        condition
+                    }
+                });
+            }
+
+            // The binding is in place before the patterns and guard run, so
+            // a failed arm discards it along with the condition.
+            operations.extend(arm.binding);
+
+            let condition = condition.ok_or("Match arm without a condition")?;
+            operations.extend(condition);
+            operations.push(Instruction::new(
+                OpCode::JumpIfFalse(arm.body.len() + arm.exit.len() + 2), // + Pop + Jump
+                line,
+            ));
+            operations.push(Instruction::new(OpCode::Pop, line));
+            self.move_loop_exits(arm.body_exits, operations.len());
+            operations.extend(arm.body);
+            operations.extend(arm.exit.iter().cloned());
+            exits.push(operations.len());
+            operations.push(Instruction::new(OpCode::Jump(0), line));
+            operations.push(Instruction::new(OpCode::Pop, line));
+            operations.extend(arm.exit);
+        }
+
+        operations.extend(fallback);
+
+        let end = operations.len();
+        for index in exits {
+            operations[index].op_code = OpCode::Jump(end - index - 1);
+        }
+
+        Ok(operations)
+    }
+
+    /// Leaves whether the matched value fits `pattern` on the stack.
+    fn pattern_test(
+        &mut self,
+        slot: usize,
+        pattern: Pattern,
+        line: usize,
+    ) -> Result<Vec<Instruction>, String> {
+        let mut test = vec![Instruction::new(OpCode::GetLocal(slot), line)];
+
+        match pattern {
+            Pattern::Literal(value) => {
+                test.push(Instruction::new(OpCode::Value(value), line));
+                test.push(Instruction::new(OpCode::Equal, line));
+            }
+            Pattern::Range(low, high, inclusive) => {
+                test.push(Instruction::new(OpCode::Value(Value::Number(low)), line));
+                test.push(Instruction::new(OpCode::Value(Value::Number(high)), line));
+                test.push(Instruction::new(OpCode::InRange(inclusive), line));
+            }
+            Pattern::Class(name, class_line) => {
+                test.extend(self.named_variable(&name, class_line, false)?);
+                test.push(Instruction::new(OpCode::Is, class_line));
+            }
+            Pattern::Variant(enumeration, variant, enum_line) => {
+                test.extend(self.named_variable(&enumeration, enum_line, false)?);
+                test.push(Instruction::new(OpCode::GetProperty(variant), enum_line));
+                test.push(Instruction::new(OpCode::Is, enum_line));
+            }
+        }
+
+        Ok(test)
+    }
+
     fn return_statement(&mut self) -> Result<Vec<Instruction>, String> {
         let mut operations = vec![];
 
@@ -453,6 +1710,15 @@ impl<'a> Parser<'a> {
             Some(FunctionType::Initializer) => {
                 return Err("Can't return a value from an initializer.".to_string());
             }
+            Some(FunctionType::Setter) => {
+                // A setter's result is always the value assigned.
+                let line = self.get_line()?;
+                self.consume(TokenType::Semicolon, "Can't return a value from a setter.")?;
+                return Ok(vec![
+                    Instruction::new(OpCode::GetLocal(1), line),
+                    Instruction::new(OpCode::Return, line),
+                ]);
+            }
             _ => (),
         }
 
@@ -464,6 +1730,20 @@ impl<'a> Parser<'a> {
             operations.push(Instruction::new(OpCode::Return, self.get_line()?));
         } else {
             operations.append(&mut self.expression()?);
+
+            // `return a, b;` returns the values as a tuple.
+            let mut count = 1;
+            while self.match_token(TokenType::Comma)? {
+                operations.append(&mut self.expression()?);
+                count += 1;
+            }
+            if count > 1 {
+                operations.push(Instruction::new(
+                    OpCode::BuildTuple(count),
+                    self.get_line()?,
+                ));
+            }
+
             self.consume(TokenType::Semicolon, "Expect ';' after return value.")?;
             operations.push(Instruction::new(OpCode::Return, self.get_line()?));
         }
@@ -476,18 +1756,23 @@ impl<'a> Parser<'a> {
         let mut operations = self.expression()?;
         self.consume(TokenType::RightParen, "Expect ')' after condition.")?;
 
-        let mut body = self.statement()?;
+        let (mut body, exits) = self.loop_body()?;
 
         operations.push(Instruction::new(
             OpCode::JumpIfFalse(body.len() + 2), // + Pop + Loop
             self.get_line()?,
         ));
         operations.push(Instruction::new(OpCode::Pop, self.get_line()?));
+        let body_start = operations.len();
         operations.append(&mut body);
         operations.push(Instruction::new(
             OpCode::Loop(operations.len() + 1), // + Loop itself
             self.get_line()?,
         ));
+        operations.push(Instruction::new(OpCode::Pop, self.get_line()?)); // for JumpIfFalse to pop "false"
+
+        let end = operations.len();
+        Self::patch_loop_exits(&mut operations, exits, body_start, end, 0);
 
         Ok(operations)
     }
@@ -496,15 +1781,22 @@ impl<'a> Parser<'a> {
         self.begin_scope();
         self.consume(TokenType::LeftParen, "Expect '(' after 'for'.")?;
 
-        let mut operations = match (
-            self.match_token(TokenType::Var),
-            self.match_token(TokenType::Semicolon),
-        ) {
-            (Ok(true), Ok(false)) => self.var_declaration(),
-            (Ok(false), Ok(true)) => Ok(vec![]), // no initializer
-            (Ok(false), Ok(false)) => self.expression_statement(),
-            (_, _) => Err("Invalid for statement".to_string()),
-        }?;
+        let mut operations = if self.match_token(TokenType::Var)? {
+            let identifier = self.consume(TokenType::Identifier, "Expect variable name")?;
+            let name = identifier.lexeme.to_string();
+            let line = identifier.line;
+
+            if self.match_token(TokenType::In)? {
+                return self.for_in_statement(name);
+            }
+
+            self.declare_variable(name.clone())?;
+            self.var_initializer(name, line)?
+        } else if self.match_token(TokenType::Semicolon)? {
+            vec![] // no initializer
+        } else {
+            self.expression_statement()?
+        };
 
         let loop_start_index = operations.len();
 
@@ -533,29 +1825,186 @@ impl<'a> Parser<'a> {
             "Expect ')' after for loop condition.",
         )?;
 
-        let mut body = self.statement()?;
+        let (mut body, exits) = self.loop_body()?;
+        let body_start = operations.len();
+        let increment_index = operations.len() + body.len();
         body.extend(increment);
         body.push(Instruction::new(
             OpCode::Loop(body.len() + operations.len() - loop_start_index + 1), // + Loop
             self.get_line()?,
         ));
 
+        operations.extend(body);
+
         if let Some(index) = condition_jump_index {
-            operations[index].op_code = OpCode::JumpIfFalse(body.len() + 1);
+            operations[index].op_code = OpCode::JumpIfFalse(operations.len() - index - 1);
+            operations.push(Instruction::new(OpCode::Pop, self.get_line()?)); // for JumpIfFalse to pop "false"
         }
 
+        let end = operations.len();
+        Self::patch_loop_exits(&mut operations, exits, body_start, end, increment_index);
+
+        operations.extend(self.end_scope()?);
+        Ok(operations)
+    }
+
+    /// Compiles `for (var name in iterable) body` after the `in` keyword.
+    ///
+    /// The iterator lives in a hidden local for the whole loop, while `name` is
+    /// a fresh local in every iteration so closures capture each element
+    /// separately. `IteratorNext` jumps past the loop once the iterator is
+    /// exhausted.
+    fn for_in_statement(&mut self, name: String) -> Result<Vec<Instruction>, String> {
+        let mut operations = self.expression()?;
+        self.consume(TokenType::RightParen, "Expect ')' after for-in iterable.")?;
+
+        let line = self.get_line()?;
+        operations.push(Instruction::new(OpCode::GetIterator, line));
+
+        self.compilation_context
+            .add_local("for iterator".to_string())?;
+        self.compilation_context.mark_initialized()?;
+        let iterator_slot = self
+            .compilation_context
+            .resolve_local("for iterator")?
+            .ok_or("Failed to resolve for-in iterator")?;
+
+        let loop_start_index = operations.len();
+        operations.push(Instruction::new(OpCode::GetLocal(iterator_slot), line));
+
+        let next_index = operations.len();
+        operations.push(Instruction::new(OpCode::IteratorNext(0), line));
+
+        self.loops.push(LoopContext {
+            depth: self.compilation_context.get_depth(),
+            exits: Vec::new(),
+        });
+
+        self.begin_scope();
+        self.compilation_context.add_local(name)?;
+        self.compilation_context.mark_initialized()?;
+
+        let body = self.statement()?;
+        let exits = self.loops.pop().map(|context| context.exits);
+        let exits = exits.unwrap_or_default();
+        let body_start = operations.len();
         operations.extend(body);
-        operations.push(Instruction::new(OpCode::Pop, self.get_line()?)); // for JumpIfFalse to pop "false"
+        operations.extend(self.end_scope()?);
+
+        operations.push(Instruction::new(
+            OpCode::Loop(operations.len() - loop_start_index + 1), // + Loop
+            self.get_line()?,
+        ));
+        operations[next_index].op_code = OpCode::IteratorNext(operations.len() - next_index - 1);
+
+        let end = operations.len();
+        Self::patch_loop_exits(&mut operations, exits, body_start, end, loop_start_index);
 
         operations.extend(self.end_scope()?);
         Ok(operations)
     }
 
+    /// Compiles the body of a `while` or `for` loop, making it the target of
+    /// any `break` or `continue` inside it. Returns the body with its exits.
+    fn loop_body(&mut self) -> Result<(Vec<Instruction>, Vec<LoopExit>), String> {
+        self.loops.push(LoopContext {
+            depth: self.compilation_context.get_depth(),
+            exits: Vec::new(),
+        });
+        let body = self.statement();
+        let exits = self.loops.pop().map(|context| context.exits);
+
+        Ok((body?, exits.unwrap_or_default()))
+    }
+
+    /// Compiles `break` and `continue`. Locals declared inside the loop body are
+    /// discarded first; `CloseUpvalue` is used for all of them because a local
+    /// may only be captured by a closure declared after this statement. The
+    /// jump is recorded in the enclosing loop, which aims it once complete.
+    fn loop_exit_statement(&mut self, keyword: TokenType) -> Result<Vec<Instruction>, String> {
+        let token = self.previous.ok_or("Unexpected end of input")?;
+        let line = token.line;
+
+        let depth = match self.loops.last() {
+            Some(loop_context) => loop_context.depth,
+            None => {
+                let message = format!("Can't use '{}' outside of a loop.", token.lexeme);
+                return self.error_at(&token, &message);
+            }
+        };
+
+        let message = format!("Expect ';' after '{}'.", token.lexeme);
+        self.consume(TokenType::Semicolon, &message)?;
+
+        let mut operations: Vec<Instruction> = self
+            .compilation_context
+            .iter()
+            .filter(|variable| variable.depth.is_some_and(|d| d > depth))
+            .map(|_| Instruction::new(OpCode::CloseUpvalue, line))
+            .collect();
+
+        if let Some(loop_context) = self.loops.last_mut() {
+            loop_context.exits.push(LoopExit {
+                index: operations.len(),
+                is_break: keyword == TokenType::Break,
+            });
+        }
+        operations.push(Instruction::new(OpCode::Jump(0), line));
+
+        Ok(operations)
+    }
+
+    /// How many exits the innermost loop has recorded so far. Taken before
+    /// compiling a statement, it marks the start of that statement's exits.
+    fn loop_exit_count(&self) -> usize {
+        self.loops
+            .last()
+            .map_or(0, |loop_context| loop_context.exits.len())
+    }
+
+    /// Moves the loop exits in `exits` by `offset`, once the statement that
+    /// holds them has been placed `offset` instructions into an enclosing one.
+    fn move_loop_exits(&mut self, exits: Range<usize>, offset: usize) {
+        if let Some(loop_context) = self.loops.last_mut() {
+            for exit in &mut loop_context.exits[exits] {
+                exit.index += offset;
+            }
+        }
+    }
+
+    /// Aims the `break` and `continue` jumps of a finished loop, whose body
+    /// starts at `body_start`, at `break_target` and `continue_target`.
+    fn patch_loop_exits(
+        operations: &mut [Instruction],
+        exits: Vec<LoopExit>,
+        body_start: usize,
+        break_target: usize,
+        continue_target: usize,
+    ) {
+        for exit in exits {
+            let index = body_start + exit.index;
+            let target = if exit.is_break {
+                break_target
+            } else {
+                continue_target
+            };
+
+            operations[index].op_code = if target > index {
+                OpCode::Jump(target - index - 1)
+            } else {
+                OpCode::Loop(index + 1 - target)
+            };
+        }
+    }
+
     fn block(&mut self) -> Result<Vec<Instruction>, String> {
         let mut operations = Vec::new();
 
         while !self.check(TokenType::RightBrace) && !self.is_at_end() {
-            operations.extend(self.declaration()?);
+            let first_exit = self.loop_exit_count();
+            let declaration = self.declaration()?;
+            self.move_loop_exits(first_exit..self.loop_exit_count(), operations.len());
+            operations.extend(declaration);
         }
 
         self.consume(TokenType::RightBrace, "Expect '}' after block")?;
@@ -589,15 +2038,12 @@ impl<'a> Parser<'a> {
                 _ => {}
             }
 
-            match self.compilation_context.pop() {
-                Some(variable) => {
-                    if variable.is_captured {
-                        instructions.push(Instruction::new(OpCode::CloseUpvalue, line));
-                    } else {
-                        instructions.push(Instruction::new(OpCode::Pop, line));
-                    }
+            if let Some(variable) = self.compilation_context.pop() {
+                if variable.is_captured {
+                    instructions.push(Instruction::new(OpCode::CloseUpvalue, line));
+                } else {
+                    instructions.push(Instruction::new(OpCode::Pop, line));
                 }
-                None => {}
             }
         }
 
@@ -661,15 +2107,17 @@ impl<'a> Parser<'a> {
             }
         }
 
-        if can_assign && self.match_token(TokenType::Equal)? {
+        if can_assign
+            && (self.match_token(TokenType::Equal)? || self.compound_assignment()?.is_some())
+        {
             return Err("Invalid assignment target.".to_string());
         }
 
         Ok(operations)
     }
 
-    fn consume(&mut self, token_type: TokenType, message: &str) -> Result<Token, String> {
-        match self.current.clone() {
+    fn consume(&mut self, token_type: TokenType, message: &str) -> Result<Token<'_>, String> {
+        match self.current {
             Some(token) if token.token_type == token_type => {
                 self.advance()?;
                 Ok(token)
@@ -710,7 +2158,7 @@ impl<'a> Parser<'a> {
                 break;
             }
 
-            self.error_at(&next, "Unexpected token")?;
+            self.scanner_error(&next)?;
         }
 
         match self.error {
@@ -725,14 +2173,26 @@ impl<'a> Parser<'a> {
         let binary = Box::new(|parser: &mut Parser| parser.binary());
         let number = Box::new(|parser: &mut Parser| parser.number());
         let literal = Box::new(|parser: &mut Parser| parser.literal());
+        let interpolation = Box::new(|parser: &mut Parser| parser.interpolation());
         let variable =
             Box::new(|parser: &mut Parser, can_assign: bool| parser.variable(can_assign));
         let and = Box::new(|parser: &mut Parser| parser.and());
         let or = Box::new(|parser: &mut Parser| parser.or());
+        let coalesce = Box::new(|parser: &mut Parser| parser.coalesce());
+        let conditional = Box::new(|parser: &mut Parser| parser.conditional());
+        let optional_dot = Box::new(|parser: &mut Parser| parser.optional_dot());
         let call = Box::new(|parser: &mut Parser| parser.call());
         let dot = Box::new(|parser: &mut Parser, can_assign: bool| parser.dot(can_assign));
         let this = Box::new(|parser: &mut Parser| parser.this());
-        let super_ = Box::new(|parser: &mut Parser| parser.super_());
+        let super_ = Box::new(|parser: &mut Parser, can_assign: bool| parser.super_(can_assign));
+        let yield_ = Box::new(|parser: &mut Parser| parser.yield_());
+        let lambda = Box::new(|parser: &mut Parser| parser.lambda());
+        let prefix_increment = Box::new(|parser: &mut Parser| parser.prefix_increment());
+        let list = Box::new(|parser: &mut Parser| parser.list());
+        let range = Box::new(|parser: &mut Parser| parser.range());
+        let open_range = Box::new(|parser: &mut Parser| parser.open_range());
+        let subscript =
+            Box::new(|parser: &mut Parser, can_assign: bool| parser.subscript(can_assign));
 
         match operator {
             TokenType::LeftParen => ParseRule {
@@ -740,6 +2200,11 @@ impl<'a> Parser<'a> {
                 infix: Some(InfixParseFn::ParseFn(call)),
                 precedence: Precedence::Call,
             },
+            TokenType::LeftBracket => ParseRule {
+                prefix: Some(PrefixParseFn::ParseFn(list)),
+                infix: Some(InfixParseFn::ParseFnCanAssign(subscript)),
+                precedence: Precedence::Call,
+            },
             TokenType::Minus => ParseRule {
                 prefix: Some(PrefixParseFn::ParseFn(unary)),
                 infix: Some(InfixParseFn::ParseFn(binary)),
@@ -750,22 +2215,68 @@ impl<'a> Parser<'a> {
                 infix: Some(InfixParseFn::ParseFn(binary)),
                 precedence: Precedence::Term,
             },
-            TokenType::Star | TokenType::Slash => ParseRule {
+            TokenType::Star | TokenType::Slash | TokenType::TildeSlash | TokenType::Percent => {
+                ParseRule {
+                    prefix: None,
+                    infix: Some(InfixParseFn::ParseFn(binary)),
+                    precedence: Precedence::Factor,
+                }
+            }
+            TokenType::StarStar => ParseRule {
+                prefix: None,
+                infix: Some(InfixParseFn::ParseFn(binary)),
+                precedence: Precedence::Exponent,
+            },
+            TokenType::Pipe => ParseRule {
+                prefix: None,
+                infix: Some(InfixParseFn::ParseFn(binary)),
+                precedence: Precedence::BitOr,
+            },
+            TokenType::Caret => ParseRule {
+                prefix: None,
+                infix: Some(InfixParseFn::ParseFn(binary)),
+                precedence: Precedence::BitXor,
+            },
+            TokenType::Ampersand => ParseRule {
+                prefix: None,
+                infix: Some(InfixParseFn::ParseFn(binary)),
+                precedence: Precedence::BitAnd,
+            },
+            TokenType::DotDot | TokenType::DotDotLess => ParseRule {
+                prefix: Some(PrefixParseFn::ParseFn(open_range)),
+                infix: Some(InfixParseFn::ParseFn(range)),
+                precedence: Precedence::Range,
+            },
+            TokenType::LessLess | TokenType::GreaterGreater => ParseRule {
                 prefix: None,
                 infix: Some(InfixParseFn::ParseFn(binary)),
-                precedence: Precedence::Factor,
+                precedence: Precedence::Shift,
             },
             TokenType::Number => ParseRule {
                 prefix: Some(PrefixParseFn::ParseFn(number)),
                 infix: None,
                 precedence: Precedence::None,
             },
-            TokenType::True | TokenType::False | TokenType::Nil | TokenType::String => ParseRule {
+            TokenType::True
+            | TokenType::False
+            | TokenType::Nil
+            | TokenType::String
+            | TokenType::RawString => ParseRule {
                 prefix: Some(PrefixParseFn::ParseFn(literal)),
                 infix: None,
                 precedence: Precedence::None,
             },
-            TokenType::Bang => ParseRule {
+            TokenType::Interpolation => ParseRule {
+                prefix: Some(PrefixParseFn::ParseFn(interpolation)),
+                infix: None,
+                precedence: Precedence::None,
+            },
+            TokenType::PlusPlus | TokenType::MinusMinus => ParseRule {
+                prefix: Some(PrefixParseFn::ParseFn(prefix_increment)),
+                infix: None,
+                precedence: Precedence::None,
+            },
+            TokenType::Bang | TokenType::Tilde => ParseRule {
                 prefix: Some(PrefixParseFn::ParseFn(unary)),
                 infix: None,
                 precedence: Precedence::None,
@@ -778,7 +2289,8 @@ impl<'a> Parser<'a> {
             TokenType::Greater
             | TokenType::GreaterEqual
             | TokenType::Less
-            | TokenType::LessEqual => ParseRule {
+            | TokenType::LessEqual
+            | TokenType::Is => ParseRule {
                 prefix: None,
                 infix: Some(InfixParseFn::ParseFn(binary)),
                 precedence: Precedence::Comparison,
@@ -798,6 +2310,21 @@ impl<'a> Parser<'a> {
                 infix: Some(InfixParseFn::ParseFn(or)),
                 precedence: Precedence::Or,
             },
+            TokenType::QuestionQuestion => ParseRule {
+                prefix: None,
+                infix: Some(InfixParseFn::ParseFn(coalesce)),
+                precedence: Precedence::Coalesce,
+            },
+            TokenType::Question => ParseRule {
+                prefix: None,
+                infix: Some(InfixParseFn::ParseFn(conditional)),
+                precedence: Precedence::Conditional,
+            },
+            TokenType::QuestionDot => ParseRule {
+                prefix: None,
+                infix: Some(InfixParseFn::ParseFn(optional_dot)),
+                precedence: Precedence::Call,
+            },
             TokenType::Dot => ParseRule {
                 prefix: None,
                 infix: Some(InfixParseFn::ParseFnCanAssign(dot)),
@@ -808,8 +2335,18 @@ impl<'a> Parser<'a> {
                 infix: None,
                 precedence: Precedence::None,
             },
-            TokenType::Super => ParseRule {
-                prefix: Some(PrefixParseFn::ParseFn(super_)),
+            TokenType::Super => ParseRule {
+                prefix: Some(PrefixParseFn::ParseFnCanAssign(super_)),
+                infix: None,
+                precedence: Precedence::None,
+            },
+            TokenType::Fun => ParseRule {
+                prefix: Some(PrefixParseFn::ParseFn(lambda)),
+                infix: None,
+                precedence: Precedence::None,
+            },
+            TokenType::Yield => ParseRule {
+                prefix: Some(PrefixParseFn::ParseFn(yield_)),
                 infix: None,
                 precedence: Precedence::None,
             },
@@ -821,8 +2358,27 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Parses `(expression)`, or a tuple such as `(a, b)` or `(a,)` when the
+    /// parentheses hold a comma.
     fn grouping(&mut self) -> Result<Vec<Instruction>, String> {
-        let expression = self.expression()?;
+        let line = self.get_line()?;
+        let mut expression = self.expression()?;
+
+        if self.match_token(TokenType::Comma)? {
+            let mut count = 1;
+            while !self.check(TokenType::RightParen) && !self.is_at_end() {
+                expression.extend(self.expression()?);
+                count += 1;
+
+                if !self.match_token(TokenType::Comma)? {
+                    break;
+                }
+            }
+
+            self.consume(TokenType::RightParen, "Expect ')' after tuple elements.")?;
+            expression.push(Instruction::new(OpCode::BuildTuple(count), line));
+            return Ok(expression);
+        }
 
         self.consume(
             TokenType::RightParen,
@@ -833,28 +2389,101 @@ impl<'a> Parser<'a> {
     }
 
     fn and(&mut self) -> Result<Vec<Instruction>, String> {
-        let mut expression = self.parse_precedence(Precedence::And)?;
-        let mut operations = vec![Instruction::new(
-            OpCode::JumpIfFalse(expression.len()),
-            self.get_line()?,
-        )];
-        operations.append(&mut expression);
+        let line = self.get_line()?;
+        let expression = self.parse_precedence(Precedence::And)?;
+
+        let mut operations = vec![
+            Instruction::new(OpCode::JumpIfFalse(expression.len() + 1), line),
+            Instruction::new(OpCode::Pop, line),
+        ];
+        operations.extend(expression);
 
-        return Ok(operations);
+        Ok(operations)
     }
 
     fn or(&mut self) -> Result<Vec<Instruction>, String> {
-        let mut operations = vec![Instruction::new(OpCode::JumpIfFalse(1), self.get_line()?)];
+        let line = self.get_line()?;
+        let expression = self.parse_precedence(Precedence::Or)?;
 
-        let mut expression = self.parse_precedence(Precedence::Or)?;
+        let mut operations = vec![
+            Instruction::new(OpCode::JumpIfFalse(1), line),
+            Instruction::new(OpCode::Jump(expression.len() + 1), line),
+            Instruction::new(OpCode::Pop, line),
+        ];
+        operations.extend(expression);
 
-        operations.push(Instruction::new(
-            OpCode::Jump(expression.len()),
-            self.get_line()?,
-        ));
-        operations.append(&mut expression);
+        Ok(operations)
+    }
+
+    /// Compiles `a ?? b`, which evaluates `b` only when `a` is nil.
+    fn coalesce(&mut self) -> Result<Vec<Instruction>, String> {
+        let line = self.get_line()?;
+        let expression = self.parse_precedence(Precedence::Or)?;
+
+        let mut operations = vec![
+            Instruction::new(OpCode::JumpIfNotNil(expression.len() + 1), line),
+            Instruction::new(OpCode::Pop, line),
+        ];
+        operations.extend(expression);
+
+        Ok(operations)
+    }
+
+    /// Compiles `condition ? then : else`. Both branches may themselves be
+    /// conditionals, so `a ? b : c ? d : e` nests to the right.
+    fn conditional(&mut self) -> Result<Vec<Instruction>, String> {
+        let line = self.get_line()?;
+        let then_branch = self.parse_precedence(Precedence::Conditional)?;
+        self.consume(
+            TokenType::Colon,
+            "Expect ':' after then branch of conditional.",
+        )?;
+        let else_branch = self.parse_precedence(Precedence::Conditional)?;
+
+        let mut operations = vec![
+            Instruction::new(OpCode::JumpIfFalse(then_branch.len() + 2), line),
+            Instruction::new(OpCode::Pop, line),
+        ];
+        operations.extend(then_branch);
+        operations.push(Instruction::new(OpCode::Jump(else_branch.len() + 1), line));
+        operations.push(Instruction::new(OpCode::Pop, line));
+        operations.extend(else_branch);
+
+        Ok(operations)
+    }
 
-        return Ok(operations);
+    /// Compiles `receiver?.name`. A nil receiver skips the rest of the call
+    /// chain, so `a?.b.c()` is nil rather than an error when `a` is nil.
+    fn optional_dot(&mut self) -> Result<Vec<Instruction>, String> {
+        let line = self.get_line()?;
+        let mut chain = self.dot(false)?;
+
+        while let Some(token) = self.current {
+            match token.token_type {
+                TokenType::Dot => {
+                    self.advance()?;
+                    chain.extend(self.dot(false)?);
+                }
+                TokenType::LeftParen => {
+                    self.advance()?;
+                    chain.extend(self.call()?);
+                }
+                TokenType::LeftBracket => {
+                    self.advance()?;
+                    chain.extend(self.subscript(false)?);
+                }
+                TokenType::QuestionDot => {
+                    self.advance()?;
+                    chain.extend(self.optional_dot()?);
+                }
+                _ => break,
+            }
+        }
+
+        let mut operations = vec![Instruction::new(OpCode::JumpIfNil(chain.len()), line)];
+        operations.extend(chain);
+
+        Ok(operations)
     }
 
     fn binary(&mut self) -> Result<Vec<Instruction>, String> {
@@ -863,10 +2492,14 @@ impl<'a> Parser<'a> {
             .ok_or("Expected binary operator, found nothing")?;
         let token_type = previous.token_type;
         let line = previous.line;
-        let precedence = self.get_rule(token_type).precedence.next().ok_or(format!(
-            "Can not determine precedence for token: {:?}",
-            token_type
-        ))?;
+        let precedence = match token_type {
+            // `**` is right-associative: `2 ** 3 ** 2` is `2 ** (3 ** 2)`.
+            TokenType::StarStar => Precedence::Exponent,
+            _ => self.get_rule(token_type).precedence.next().ok_or(format!(
+                "Can not determine precedence for token: {:?}",
+                token_type
+            ))?,
+        };
         let mut operations = self.parse_precedence(precedence)?;
 
         match token_type {
@@ -874,6 +2507,16 @@ impl<'a> Parser<'a> {
             TokenType::Minus => operations.push(Instruction::new(OpCode::Subtract, line)),
             TokenType::Star => operations.push(Instruction::new(OpCode::Multiply, line)),
             TokenType::Slash => operations.push(Instruction::new(OpCode::Divide, line)),
+            TokenType::TildeSlash => operations.push(Instruction::new(OpCode::IntegerDivide, line)),
+            TokenType::Percent => operations.push(Instruction::new(OpCode::Modulo, line)),
+            TokenType::StarStar => operations.push(Instruction::new(OpCode::Power, line)),
+            TokenType::Ampersand => operations.push(Instruction::new(OpCode::BitAnd, line)),
+            TokenType::Pipe => operations.push(Instruction::new(OpCode::BitOr, line)),
+            TokenType::Caret => operations.push(Instruction::new(OpCode::BitXor, line)),
+            TokenType::LessLess => operations.push(Instruction::new(OpCode::ShiftLeft, line)),
+            TokenType::GreaterGreater => {
+                operations.push(Instruction::new(OpCode::ShiftRight, line))
+            }
             TokenType::EqualEqual => operations.push(Instruction::new(OpCode::Equal, line)),
             TokenType::BangEqual => {
                 operations.push(Instruction::new(OpCode::Equal, line));
@@ -885,6 +2528,7 @@ impl<'a> Parser<'a> {
                 operations.push(Instruction::new(OpCode::Not, line));
             }
             TokenType::Less => operations.push(Instruction::new(OpCode::Less, line)),
+            TokenType::Is => operations.push(Instruction::new(OpCode::Is, line)),
             TokenType::LessEqual => {
                 operations.push(Instruction::new(OpCode::Greater, line));
                 operations.push(Instruction::new(OpCode::Not, line));
@@ -907,6 +2551,7 @@ impl<'a> Parser<'a> {
         match previous.token_type {
             TokenType::Minus => instructions.push(Instruction::new(OpCode::Negate, previous.line)),
             TokenType::Bang => instructions.push(Instruction::new(OpCode::Not, previous.line)),
+            TokenType::Tilde => instructions.push(Instruction::new(OpCode::BitNot, previous.line)),
             _ => {
                 return Err(format!(
                     "Unexpected unary operator type: {:?}",
@@ -923,16 +2568,26 @@ impl<'a> Parser<'a> {
             .previous
             .ok_or("Expected number when parsing number, found nothing")?;
 
-        previous
-            .lexeme
-            .parse::<f64>()
-            .map(|value| {
-                vec![Instruction::new(
-                    OpCode::Value(Value::Number(value)),
-                    previous.line,
-                )]
-            })
-            .map_err(|e| e.to_string())
+        let digits = previous.lexeme.replace('_', "");
+        let radix = match digits.get(..2) {
+            Some("0x" | "0X") => Some(16),
+            Some("0b" | "0B") => Some(2),
+            _ => None,
+        };
+
+        let value = match radix {
+            // Integers beyond 2^53 lose precision, as they would written in decimal.
+            Some(radix) => digits[2..]
+                .chars()
+                .filter_map(|c| c.to_digit(radix))
+                .fold(0.0, |value, digit| value * radix as f64 + digit as f64),
+            None => digits.parse::<f64>().map_err(|e| e.to_string())?,
+        };
+
+        Ok(vec![Instruction::new(
+            OpCode::Value(Value::Number(value)),
+            previous.line,
+        )])
     }
 
     fn literal(&mut self) -> Result<Vec<Instruction>, String> {
@@ -946,7 +2601,8 @@ impl<'a> Parser<'a> {
                 line,
             )]),
             TokenType::Nil => Ok(vec![Instruction::new(OpCode::Value(Value::Nil), line)]),
-            TokenType::String => Ok(vec![Instruction::new(
+            TokenType::String => self.string_segment(&previous),
+            TokenType::RawString => Ok(vec![Instruction::new(
                 OpCode::Value(Value::String(Rc::new(lexeme.to_string()))),
                 line,
             )]),
@@ -957,6 +2613,48 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Compiles `"a ${x} b"` as `"a " + x + " b"`, converting each interpolated
+    /// value to a string first.
+    fn interpolation(&mut self) -> Result<Vec<Instruction>, String> {
+        let segment = self.previous.ok_or("Expected string, found nothing")?;
+        let mut instructions = self.string_segment(&segment)?;
+
+        loop {
+            let line = self.get_line()?;
+            instructions.extend(self.expression()?);
+            instructions.push(Instruction::new(OpCode::Stringify, line));
+            instructions.push(Instruction::new(OpCode::Add, line));
+
+            let done = !self.match_token(TokenType::Interpolation)?;
+            if done {
+                self.consume(
+                    TokenType::String,
+                    "Expect '}' after interpolated expression.",
+                )?;
+            }
+
+            let segment = self.previous.ok_or("Expected string, found nothing")?;
+            if !segment.lexeme.is_empty() {
+                instructions.extend(self.string_segment(&segment)?);
+                instructions.push(Instruction::new(OpCode::Add, segment.line));
+            }
+
+            if done {
+                return Ok(instructions);
+            }
+        }
+    }
+
+    fn string_segment(&mut self, token: &Token<'a>) -> Result<Vec<Instruction>, String> {
+        match unescape(token.lexeme) {
+            Ok(string) => Ok(vec![Instruction::new(
+                OpCode::Value(Value::String(Rc::new(string))),
+                token.line,
+            )]),
+            Err(message) => self.error_at(token, &message),
+        }
+    }
+
     /// Parses a variable expression.
     ///
     /// This function is called when encountering a variable in an expression.
@@ -966,7 +2664,7 @@ impl<'a> Parser<'a> {
     /// # Arguments
     ///
     /// * `can_assign` - If `true`, assignment to this variable is allowed (e.g., in `var a = 1;`).
-    ///                  If `false`, assignment is not allowed (e.g., in `a + 1`).
+    ///   If `false`, assignment is not allowed (e.g., in `a + 1`).
     ///
     /// # Returns
     ///
@@ -984,33 +2682,304 @@ impl<'a> Parser<'a> {
     }
 
     fn call(&mut self) -> Result<Vec<Instruction>, String> {
-        let (mut instructions, count) = self.arguments()?;
+        let arguments = self.arguments()?;
         let line = self.previous.ok_or("Unexpected end of input")?.line;
 
-        instructions.push(Instruction::new(OpCode::Call(count), line));
-
-        Ok(instructions)
+        Ok(match arguments {
+            Arguments::Fixed(mut instructions, count) => {
+                instructions.push(Instruction::new(OpCode::Call(count), line));
+                instructions
+            }
+            Arguments::Spread(mut instructions) => {
+                instructions.push(Instruction::new(OpCode::CallSpread, line));
+                instructions
+            }
+        })
     }
 
     fn dot(&mut self, can_assign: bool) -> Result<Vec<Instruction>, String> {
-        let identifier = self.consume(TokenType::Identifier, "Expected identifier after '.'.")?;
-        let lexeme = identifier.lexeme.to_string();
+        let lexeme = self.member_name("Expected identifier after '.'.")?;
 
         let line = self.previous.ok_or("Unexpected end of input")?.line;
 
         if can_assign && self.match_token(TokenType::Equal)? {
+            self.declare_member(&lexeme);
             let mut instructions = self.expression()?;
             instructions.push(Instruction::new(OpCode::SetProperty(lexeme), line));
-            Ok(instructions)
-        } else if self.match_token(TokenType::LeftParen)? {
-            let (mut instructions, count) = self.arguments()?;
-            instructions.push(Instruction::new(OpCode::Invoke(lexeme, count), line));
-            Ok(instructions)
+            return Ok(instructions);
+        }
+
+        if can_assign {
+            if let Some(operator) = self.compound_assignment()? {
+                // The receiver is evaluated once and duplicated for the write.
+                let mut instructions = vec![
+                    Instruction::new(OpCode::Dup(0), line),
+                    Instruction::new(OpCode::GetProperty(lexeme.clone()), line),
+                ];
+                instructions.extend(self.expression()?);
+                instructions.push(Instruction::new(operator, line));
+                instructions.push(Instruction::new(OpCode::SetProperty(lexeme), line));
+                return Ok(instructions);
+            }
+        }
+
+        if self.match_token(TokenType::LeftParen)? {
+            match self.arguments()? {
+                Arguments::Fixed(mut instructions, count) => {
+                    instructions.push(Instruction::new(OpCode::Invoke(lexeme, count), line));
+                    Ok(instructions)
+                }
+                Arguments::Spread(arguments) => {
+                    let mut instructions =
+                        vec![Instruction::new(OpCode::GetProperty(lexeme), line)];
+                    instructions.extend(arguments);
+                    instructions.push(Instruction::new(OpCode::CallSpread, line));
+                    Ok(instructions)
+                }
+            }
+        } else if let Some(operator) = self.increment()? {
+            // Keep the old value beneath the receiver as the result: [old, receiver, new].
+            Ok(vec![
+                Instruction::new(OpCode::Dup(0), line),
+                Instruction::new(OpCode::GetProperty(lexeme.clone()), line),
+                Instruction::new(OpCode::Dup(0), line),
+                Instruction::new(OpCode::Bury(2), line),
+                Instruction::new(OpCode::Value(Value::Number(1.0)), line),
+                Instruction::new(operator, line),
+                Instruction::new(OpCode::SetProperty(lexeme), line),
+                Instruction::new(OpCode::Pop, line),
+            ])
         } else {
             Ok(vec![Instruction::new(OpCode::GetProperty(lexeme), line)])
         }
     }
 
+    /// Consumes the name of a property or method. A private `#name` is
+    /// qualified with the enclosing class, and is an error outside of one.
+    fn member_name(&mut self, message: &str) -> Result<String, String> {
+        if self.match_token(TokenType::PrivateName)? {
+            let token = self.previous.ok_or("Unexpected end of input")?;
+            return match self.classes.last_mut() {
+                Some(class) => {
+                    let key = private_key(&class.name, token.lexeme);
+                    class
+                        .used
+                        .push((key.clone(), token.lexeme.to_string(), token.line));
+                    Ok(key)
+                }
+                None => Err(self.format_error(
+                    token.line,
+                    token.lexeme,
+                    "Can't use a private member outside of a class.",
+                )),
+            };
+        }
+
+        self.identifier_or_keyword(message)
+    }
+
+    /// Consumes an identifier, or a reserved word used as a property or
+    /// method name, as in `this.match` or `with() { ... }`.
+    fn identifier_or_keyword(&mut self, message: &str) -> Result<String, String> {
+        if self
+            .current
+            .is_some_and(|token| token.token_type.is_keyword())
+        {
+            self.advance()?;
+            let token = self.previous.ok_or("Unexpected end of input")?;
+            return Ok(token.lexeme.to_string());
+        }
+
+        let token = self.consume(TokenType::Identifier, message)?;
+        Ok(token.lexeme.to_string())
+    }
+
+    /// Whether the current token is the member modifier `modifier`, rather
+    /// than the name of a method, getter or setter such as `static() { ... }`.
+    /// `abstract` is an identifier, and a modifier only in this position.
+    fn check_modifier(&self, modifier: &str) -> bool {
+        self.current.is_some_and(|token| {
+            matches!(
+                token.token_type,
+                TokenType::Static | TokenType::Var | TokenType::Identifier
+            ) && token.lexeme == modifier
+        }) && !self.check_next(TokenType::LeftParen)
+            && !self.check_next(TokenType::LeftBrace)
+            && !self.check_next(TokenType::Equal)
+    }
+
+    /// Records that the enclosing class declares or assigns the member `key`.
+    fn declare_member(&mut self, key: &str) {
+        if let Some(class) = self.classes.last_mut() {
+            class.declared.insert(key.to_string());
+        }
+    }
+
+    /// Leaves the innermost class or trait, rejecting a private member its
+    /// body uses but never declares or assigns.
+    fn end_class(&mut self) -> Result<(), String> {
+        let class = self.classes.pop().ok_or("Expected enclosing class")?;
+        match class
+            .used
+            .iter()
+            .find(|(key, ..)| !class.declared.contains(key))
+        {
+            Some((_, name, line)) => Err(self.format_error(
+                *line,
+                name,
+                &format!("{} never declares or assigns '{}'.", class.name, name),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Compiles a list literal `[a, b]`, or a map literal `[key: value]` when
+    /// the first element is followed by a colon. `[:]` is the empty map.
+    fn list(&mut self) -> Result<Vec<Instruction>, String> {
+        let line = self.get_line()?;
+
+        if self.match_token(TokenType::Colon)? {
+            self.consume(
+                TokenType::RightBracket,
+                "Expect ']' after ':' in empty map.",
+            )?;
+            return Ok(vec![Instruction::new(OpCode::BuildMap(0), line)]);
+        }
+
+        let mut instructions = Vec::new();
+        let mut count = 0;
+        let mut is_map = false;
+
+        if !self.check(TokenType::RightBracket) {
+            loop {
+                instructions.extend(self.expression()?);
+
+                if count == 0 {
+                    is_map = self.match_token(TokenType::Colon)?;
+                } else if is_map {
+                    self.consume(TokenType::Colon, "Expect ':' after map key.")?;
+                }
+                if is_map {
+                    instructions.extend(self.expression()?);
+                }
+                count += 1;
+
+                if !self.match_token(TokenType::Comma)? {
+                    break;
+                }
+            }
+        }
+
+        if is_map {
+            self.consume(TokenType::RightBracket, "Expect ']' after map entries.")?;
+            instructions.push(Instruction::new(OpCode::BuildMap(count), line));
+        } else {
+            self.consume(TokenType::RightBracket, "Expect ']' after list elements.")?;
+            instructions.push(Instruction::new(OpCode::BuildList(count), line));
+        }
+
+        Ok(instructions)
+    }
+
+    /// Compiles the rest of `start..end` or `start..<end` once the operator
+    /// is consumed, with an optional `step` after the end. The end may be
+    /// left out, as in `xs[2..]`.
+    fn range(&mut self) -> Result<Vec<Instruction>, String> {
+        let operator = self.previous.ok_or("Unexpected end of input")?;
+        let inclusive = operator.token_type == TokenType::DotDot;
+        let line = operator.line;
+
+        let mut operations = if self.range_bound_follows() {
+            self.parse_precedence(Precedence::Term)?
+        } else {
+            vec![Instruction::new(OpCode::Value(Value::Nil), line)]
+        };
+
+        if self.check_step() {
+            self.advance()?;
+            operations.extend(self.parse_precedence(Precedence::Term)?);
+        } else {
+            operations.push(Instruction::new(OpCode::Value(Value::Nil), line));
+        }
+
+        operations.push(Instruction::new(OpCode::BuildRange(inclusive), line));
+        Ok(operations)
+    }
+
+    /// Compiles a range without a start, such as `..<3`.
+    fn open_range(&mut self) -> Result<Vec<Instruction>, String> {
+        let line = self.get_line()?;
+        let mut operations = vec![Instruction::new(OpCode::Value(Value::Nil), line)];
+        operations.extend(self.range()?);
+        Ok(operations)
+    }
+
+    /// Whether the current token starts the end of a range, rather than
+    /// closing the expression around an open-ended one.
+    ///
+    /// `step` here is a variable unless the token after it can start the
+    /// step but not continue an expression, so `0..step`, `0..step + 1` and
+    /// `0..step step 2` end at the variable while `0.. step 2` is open-ended.
+    fn range_bound_follows(&self) -> bool {
+        if self.check_step() {
+            let starts_step = self.scanner.clone().next().is_some_and(|token| {
+                let rule = self.get_rule(token.token_type);
+                rule.prefix.is_some() && rule.infix.is_none() && token.lexeme != "step"
+            });
+            return !starts_step;
+        }
+
+        self.current
+            .is_some_and(|token| self.get_rule(token.token_type).prefix.is_some())
+    }
+
+    /// `step` is only a keyword right after a range's bounds.
+    fn check_step(&self) -> bool {
+        self.check_word("step")
+    }
+
+    fn subscript(&mut self, can_assign: bool) -> Result<Vec<Instruction>, String> {
+        let line = self.get_line()?;
+        let mut instructions = self.expression()?;
+        self.consume(TokenType::RightBracket, "Expect ']' after index.")?;
+
+        if can_assign && self.match_token(TokenType::Equal)? {
+            instructions.extend(self.expression()?);
+            instructions.push(Instruction::new(OpCode::SetIndex, line));
+            return Ok(instructions);
+        }
+
+        if can_assign {
+            if let Some(operator) = self.compound_assignment()? {
+                // The target and index are evaluated once and duplicated for the write.
+                instructions.push(Instruction::new(OpCode::Dup(1), line));
+                instructions.push(Instruction::new(OpCode::Dup(1), line));
+                instructions.push(Instruction::new(OpCode::GetIndex, line));
+                instructions.extend(self.expression()?);
+                instructions.push(Instruction::new(operator, line));
+                instructions.push(Instruction::new(OpCode::SetIndex, line));
+                return Ok(instructions);
+            }
+        }
+
+        if let Some(operator) = self.increment()? {
+            // Keep the old value beneath the target as the result: [old, list, index, new].
+            instructions.push(Instruction::new(OpCode::Dup(1), line));
+            instructions.push(Instruction::new(OpCode::Dup(1), line));
+            instructions.push(Instruction::new(OpCode::GetIndex, line));
+            instructions.push(Instruction::new(OpCode::Dup(0), line));
+            instructions.push(Instruction::new(OpCode::Bury(3), line));
+            instructions.push(Instruction::new(OpCode::Value(Value::Number(1.0)), line));
+            instructions.push(Instruction::new(operator, line));
+            instructions.push(Instruction::new(OpCode::SetIndex, line));
+            instructions.push(Instruction::new(OpCode::Pop, line));
+        } else {
+            instructions.push(Instruction::new(OpCode::GetIndex, line));
+        }
+
+        Ok(instructions)
+    }
+
     fn this(&mut self) -> Result<Vec<Instruction>, String> {
         if !self.in_class {
             return self.error_at(
@@ -1022,7 +2991,7 @@ impl<'a> Parser<'a> {
         self.variable(false)
     }
 
-    fn super_(&mut self) -> Result<Vec<Instruction>, String> {
+    fn super_(&mut self, can_assign: bool) -> Result<Vec<Instruction>, String> {
         if !self.in_class {
             return self.error_at(
                 &self.previous.unwrap(),
@@ -1030,6 +2999,10 @@ impl<'a> Parser<'a> {
             );
         }
 
+        if self.in_trait {
+            return self.error_at(&self.previous.unwrap(), "Can't use 'super' in a trait.");
+        }
+
         if !self.has_superclass {
             return self.error_at(
                 &self.previous.unwrap(),
@@ -1038,25 +3011,34 @@ impl<'a> Parser<'a> {
         }
 
         self.consume(TokenType::Dot, "Expect '.' after 'super'.")?;
-        let method_name_token =
-            self.consume(TokenType::Identifier, "Expect superclass method name.")?;
-
-        let line = method_name_token.line;
-        let method_name = method_name_token.lexeme.to_string();
+        let method_name = self.identifier_or_keyword("Expect superclass method name.")?;
+        let line = self.previous.ok_or("Unexpected end of input")?.line;
 
         let mut instructions = vec![];
 
         instructions.extend(self.named_variable("this", line, false)?);
 
-        if self.match_token(TokenType::LeftParen)? {
-            let (args_instructions, count) = self.arguments()?;
-
-            instructions.extend(args_instructions);
+        if can_assign && self.match_token(TokenType::Equal)? {
+            instructions.extend(self.expression()?);
             instructions.extend(self.named_variable("super", line, false)?);
-            instructions.push(Instruction::new(
-                OpCode::SuperInvoke(method_name, count),
-                line,
-            ));
+            instructions.push(Instruction::new(OpCode::SetSuper(method_name), line));
+        } else if self.match_token(TokenType::LeftParen)? {
+            match self.arguments()? {
+                Arguments::Fixed(args_instructions, count) => {
+                    instructions.extend(args_instructions);
+                    instructions.extend(self.named_variable("super", line, false)?);
+                    instructions.push(Instruction::new(
+                        OpCode::SuperInvoke(method_name, count),
+                        line,
+                    ));
+                }
+                Arguments::Spread(args_instructions) => {
+                    instructions.extend(self.named_variable("super", line, false)?);
+                    instructions.push(Instruction::new(OpCode::GetSuper(method_name), line));
+                    instructions.extend(args_instructions);
+                    instructions.push(Instruction::new(OpCode::CallSpread, line));
+                }
+            }
         } else {
             instructions.extend(self.named_variable("super", line, false)?);
             instructions.push(Instruction::new(OpCode::GetSuper(method_name), line));
@@ -1065,6 +3047,30 @@ impl<'a> Parser<'a> {
         Ok(instructions)
     }
 
+    /// Parses a `yield` expression. The operand is optional; a bare `yield`
+    /// hands `nil` to the resumer. The expression evaluates to the value passed
+    /// to the next `resume`.
+    fn yield_(&mut self) -> Result<Vec<Instruction>, String> {
+        let line = self.get_line()?;
+
+        if matches!(self.function_types.last(), Some(FunctionType::Script)) {
+            return self.error_at(&self.previous.unwrap(), "Can't yield from top-level code.");
+        }
+
+        let mut instructions = if self.check(TokenType::Semicolon)
+            || self.check(TokenType::RightParen)
+            || self.check(TokenType::Comma)
+        {
+            vec![Instruction::new(OpCode::Value(Value::Nil), line)]
+        } else {
+            self.parse_precedence(Precedence::Assignment)?
+        };
+
+        instructions.push(Instruction::new(OpCode::Yield, line));
+
+        Ok(instructions)
+    }
+
     fn named_variable(
         &mut self,
         name: &str,
@@ -1074,12 +3080,38 @@ impl<'a> Parser<'a> {
         let mut set_operation = OpCode::SetGlobal(name.to_string());
         let mut get_operation = OpCode::GetGlobal(name.to_string());
 
+        let mut is_const = false;
+
         if let Some(local) = self.compilation_context.resolve_local(name)? {
-            set_operation = OpCode::SetLocal(local.clone());
-            get_operation = OpCode::GetLocal(local.clone());
+            set_operation = OpCode::SetLocal(local);
+            get_operation = OpCode::GetLocal(local);
+            is_const = self.compilation_context.is_const_local(local);
         } else if let Some(upvalue) = self.compilation_context.resolve_upvalue(name)? {
             set_operation = OpCode::SetUpvalue(upvalue);
             get_operation = OpCode::GetUpvalue(upvalue);
+            is_const = self.compilation_context.is_const_upvalue(upvalue);
+        }
+
+        let assigns = can_assign
+            && self.current.is_some_and(|token| {
+                matches!(
+                    token.token_type,
+                    TokenType::Equal
+                        | TokenType::PlusEqual
+                        | TokenType::MinusEqual
+                        | TokenType::StarEqual
+                        | TokenType::SlashEqual
+                        | TokenType::PercentEqual
+                )
+            });
+        let increments = self.check(TokenType::PlusPlus) || self.check(TokenType::MinusMinus);
+
+        if is_const && (assigns || increments) {
+            return Err(self.format_error(
+                line,
+                name,
+                &format!("Cannot assign to constant '{}'.", name),
+            ));
         }
 
         if can_assign && self.match_token(TokenType::Equal)? {
@@ -1088,18 +3120,132 @@ impl<'a> Parser<'a> {
             return Ok(operations);
         }
 
+        if can_assign {
+            if let Some(operator) = self.compound_assignment()? {
+                let mut operations = vec![Instruction::new(get_operation, line)];
+                operations.extend(self.expression()?);
+                operations.push(Instruction::new(operator, line));
+                operations.push(Instruction::new(set_operation, line));
+                return Ok(operations);
+            }
+        }
+
+        if let Some(operator) = self.increment()? {
+            // Postfix: the expression evaluates to the value before the update.
+            return Ok(vec![
+                Instruction::new(get_operation, line),
+                Instruction::new(OpCode::Dup(0), line),
+                Instruction::new(OpCode::Value(Value::Number(1.0)), line),
+                Instruction::new(operator, line),
+                Instruction::new(set_operation, line),
+                Instruction::new(OpCode::Pop, line),
+            ]);
+        }
+
         Ok(vec![Instruction::new(get_operation, line)])
     }
 
-    fn arguments(&mut self) -> Result<(Vec<Instruction>, usize), String> {
-        let mut arguments = Vec::new();
-        let mut count = 0;
+    /// Consumes a compound assignment operator such as `+=`, returning the
+    /// arithmetic it applies.
+    fn compound_assignment(&mut self) -> Result<Option<OpCode>, String> {
+        let operator = match self.current.map(|token| token.token_type) {
+            Some(TokenType::PlusEqual) => OpCode::Add,
+            Some(TokenType::MinusEqual) => OpCode::Subtract,
+            Some(TokenType::StarEqual) => OpCode::Multiply,
+            Some(TokenType::SlashEqual) => OpCode::Divide,
+            Some(TokenType::PercentEqual) => OpCode::Modulo,
+            _ => return Ok(None),
+        };
+
+        self.advance()?;
+        Ok(Some(operator))
+    }
+
+    /// Consumes a postfix `++` or `--`, returning the arithmetic it applies.
+    fn increment(&mut self) -> Result<Option<OpCode>, String> {
+        let operator = match self.current.map(|token| token.token_type) {
+            Some(TokenType::PlusPlus) => OpCode::Add,
+            Some(TokenType::MinusMinus) => OpCode::Subtract,
+            _ => return Ok(None),
+        };
+
+        self.advance()?;
+        Ok(Some(operator))
+    }
+
+    /// Compiles prefix `++x` or `--x`. The operand is compiled as an ordinary
+    /// expression and its final read is rewritten into a read-modify-write.
+    fn prefix_increment(&mut self) -> Result<Vec<Instruction>, String> {
+        let token = self.previous.ok_or("Expected operator, found nothing")?;
+        let line = token.line;
+        let operator = match token.token_type {
+            TokenType::PlusPlus => OpCode::Add,
+            _ => OpCode::Subtract,
+        };
+
+        let mut operations = self.parse_precedence(Precedence::Call)?;
+        let one = Instruction::new(OpCode::Value(Value::Number(1.0)), line);
+
+        let update = match operations.pop().map(|instruction| instruction.op_code) {
+            Some(OpCode::GetLocal(index)) if self.compilation_context.is_const_local(index) => {
+                return self.error_at(&token, "Cannot increment a constant.");
+            }
+            Some(OpCode::GetUpvalue(index)) if self.compilation_context.is_const_upvalue(index) => {
+                return self.error_at(&token, "Cannot increment a constant.");
+            }
+            Some(OpCode::GetLocal(index)) => vec![
+                Instruction::new(OpCode::GetLocal(index), line),
+                one,
+                Instruction::new(operator, line),
+                Instruction::new(OpCode::SetLocal(index), line),
+            ],
+            Some(OpCode::GetUpvalue(index)) => vec![
+                Instruction::new(OpCode::GetUpvalue(index), line),
+                one,
+                Instruction::new(operator, line),
+                Instruction::new(OpCode::SetUpvalue(index), line),
+            ],
+            Some(OpCode::GetGlobal(name)) => vec![
+                Instruction::new(OpCode::GetGlobal(name.clone()), line),
+                one,
+                Instruction::new(operator, line),
+                Instruction::new(OpCode::SetGlobal(name), line),
+            ],
+            Some(OpCode::GetProperty(name)) => vec![
+                Instruction::new(OpCode::Dup(0), line),
+                Instruction::new(OpCode::GetProperty(name.clone()), line),
+                one,
+                Instruction::new(operator, line),
+                Instruction::new(OpCode::SetProperty(name), line),
+            ],
+            Some(OpCode::GetIndex) => vec![
+                Instruction::new(OpCode::Dup(1), line),
+                Instruction::new(OpCode::Dup(1), line),
+                Instruction::new(OpCode::GetIndex, line),
+                one,
+                Instruction::new(operator, line),
+                Instruction::new(OpCode::SetIndex, line),
+            ],
+            // `--` on anything else keeps its old meaning of two negations.
+            Some(op_code) if token.token_type == TokenType::MinusMinus => vec![
+                Instruction::new(op_code, line),
+                Instruction::new(OpCode::Negate, line),
+                Instruction::new(OpCode::Negate, line),
+            ],
+            _ => return self.error_at(&token, "Invalid increment target."),
+        };
+
+        operations.extend(update);
+        Ok(operations)
+    }
+
+    fn arguments(&mut self) -> Result<Arguments, String> {
+        let mut segments = Vec::new();
 
         if !self.match_token(TokenType::RightParen)? {
             loop {
-                count += 1;
-                let arg = self.expression()?;
-                arguments.extend(arg);
+                let is_spread = self.match_token(TokenType::DotDotDot)?;
+                segments.push((self.expression()?, is_spread));
 
                 if !self.match_token(TokenType::Comma)? {
                     self.consume(TokenType::RightParen, "Expected ')' after arguments.")?;
@@ -1108,7 +3254,46 @@ impl<'a> Parser<'a> {
             }
         }
 
-        Ok((arguments, count))
+        if segments.len() > 255 {
+            return Err("Cannot have more than 255 arguments".to_string());
+        }
+
+        if !segments.iter().any(|(_, is_spread)| *is_spread) {
+            let count = segments.len();
+            return Ok(Arguments::Fixed(
+                segments.into_iter().flat_map(|(arg, _)| arg).collect(),
+                count,
+            ));
+        }
+
+        // Collect every argument into one list: runs of plain arguments are
+        // wrapped in a list first, then each piece extends the result.
+        let line = self.get_line()?;
+        let mut instructions = vec![Instruction::new(OpCode::BuildList(0), line)];
+        let mut pending = 0;
+
+        for (arg, is_spread) in segments {
+            if is_spread && pending > 0 {
+                instructions.push(Instruction::new(OpCode::BuildList(pending), line));
+                instructions.push(Instruction::new(OpCode::ExtendList, line));
+                pending = 0;
+            }
+
+            instructions.extend(arg);
+
+            if is_spread {
+                instructions.push(Instruction::new(OpCode::ExtendList, line));
+            } else {
+                pending += 1;
+            }
+        }
+
+        if pending > 0 {
+            instructions.push(Instruction::new(OpCode::BuildList(pending), line));
+            instructions.push(Instruction::new(OpCode::ExtendList, line));
+        }
+
+        Ok(Arguments::Spread(instructions))
     }
 
     fn match_token(&mut self, token_type: TokenType) -> Result<bool, String> {
@@ -1127,6 +3312,21 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Whether the current token is the identifier `word`, which acts as a
+    /// keyword only where the grammar expects it.
+    fn check_word(&self, word: &str) -> bool {
+        self.current
+            .is_some_and(|token| token.token_type == TokenType::Identifier && token.lexeme == word)
+    }
+
+    /// Checks the token after `current` without consuming anything.
+    fn check_next(&self, token_type: TokenType) -> bool {
+        match self.scanner.clone().next() {
+            Some(token) => token.token_type == token_type,
+            None => false,
+        }
+    }
+
     #[allow(unreachable_code)]
     fn syncronize(&mut self) -> Result<(), String> {
         self.panic_mode = false;
@@ -1143,10 +3343,14 @@ impl<'a> Parser<'a> {
 
             match self.current.unwrap().token_type {
                 TokenType::Class
+                | TokenType::Enum
+                | TokenType::Trait
                 | TokenType::Fun
                 | TokenType::Var
+                | TokenType::Const
                 | TokenType::For
                 | TokenType::If
+                | TokenType::Match
                 | TokenType::While
                 | TokenType::Print
                 | TokenType::Return => return Ok(()),
@@ -1181,57 +3385,68 @@ impl<'a> Parser<'a> {
         Ok(self.previous.ok_or("Cannot get current line")?.line)
     }
 
+    /// Reports an error token from the scanner, whose lexeme is the message.
+    fn scanner_error(&mut self, token: &Token<'a>) -> Result<(), String> {
+        if self.panic_mode {
+            return Ok(());
+        }
+
+        self.panic_mode = true;
+
+        let error_string = format!("[line {}] Error: {}", token.line, token.lexeme);
+        self.error = Some(error_string.clone());
+
+        Err(error_string)
+    }
+
     fn format_error(&self, line: usize, lexeme: &str, message: &str) -> String {
         format!("[line {}] Error at {}: {}", line, lexeme, message)
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Precedence {
     None,
     Assignment,
+    Conditional,
+    Coalesce,
     Or,
     And,
     Equality,
     Comparison,
+    BitOr,
+    BitXor,
+    BitAnd,
+    Shift,
+    Range,
     Term,
     Factor,
     Unary,
+    Exponent,
     Call,
     Primary,
 }
 
-impl PartialEq for Precedence {
-    fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Precedence::None, Precedence::None) => true,
-            (Precedence::Assignment, Precedence::Assignment) => true,
-            (Precedence::Or, Precedence::Or) => true,
-            (Precedence::And, Precedence::And) => true,
-            (Precedence::Equality, Precedence::Equality) => true,
-            (Precedence::Comparison, Precedence::Comparison) => true,
-            (Precedence::Term, Precedence::Term) => true,
-            (Precedence::Factor, Precedence::Factor) => true,
-            (Precedence::Unary, Precedence::Unary) => true,
-            (Precedence::Call, Precedence::Call) => true,
-            (Precedence::Primary, Precedence::Primary) => true,
-            _ => false,
-        }
-    }
-}
-
 impl Precedence {
     pub fn next(&self) -> Option<Precedence> {
         match self {
             Precedence::None => Some(Precedence::Assignment),
-            Precedence::Assignment => Some(Precedence::Or),
+            Precedence::Assignment => Some(Precedence::Conditional),
+            Precedence::Conditional => Some(Precedence::Coalesce),
+            Precedence::Coalesce => Some(Precedence::Or),
             Precedence::Or => Some(Precedence::And),
             Precedence::And => Some(Precedence::Equality),
             Precedence::Equality => Some(Precedence::Comparison),
-            Precedence::Comparison => Some(Precedence::Term),
+            Precedence::Comparison => Some(Precedence::BitOr),
+            Precedence::BitOr => Some(Precedence::BitXor),
+            Precedence::BitXor => Some(Precedence::BitAnd),
+            Precedence::BitAnd => Some(Precedence::Shift),
+            Precedence::Shift => Some(Precedence::Range),
+            Precedence::Range => Some(Precedence::Term),
             Precedence::Term => Some(Precedence::Factor),
             Precedence::Factor => Some(Precedence::Unary),
-            Precedence::Unary => Some(Precedence::Call),
+            Precedence::Unary => Some(Precedence::Exponent),
+            Precedence::Exponent => Some(Precedence::Call),
             Precedence::Call => Some(Precedence::Primary),
             Precedence::Primary => None,
         }
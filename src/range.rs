@@ -0,0 +1,83 @@
+use std::fmt;
+
+/// A range of numbers built by `start..end` (end included) or `start..<end`
+/// (end excluded), optionally followed by `step`. Either bound may be left
+/// out: an open end never stops, and a missing start is only meaningful
+/// when slicing, where it means the first element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Range {
+    pub start: Option<f64>,
+    pub end: Option<f64>,
+    pub step: f64,
+    pub inclusive: bool,
+}
+
+impl Range {
+    pub fn new(start: Option<f64>, end: Option<f64>, step: f64, inclusive: bool) -> Self {
+        Self {
+            start,
+            end,
+            step,
+            inclusive,
+        }
+    }
+
+    /// The number at `index`, or `None` past the end of the range.
+    ///
+    /// Computed from the start rather than by adding up steps, so fractional
+    /// steps don't accumulate rounding errors.
+    pub fn get(&self, index: usize) -> Option<f64> {
+        let value = self.start? + index as f64 * self.step;
+        let end = match self.end {
+            Some(end) => end,
+            None => return Some(value),
+        };
+
+        let before_end = if self.step > 0.0 {
+            value < end
+        } else {
+            value > end
+        };
+        (before_end || (self.inclusive && value == end)).then_some(value)
+    }
+
+    /// How many numbers the range holds, or `None` when a bound is missing
+    /// or there are too many to count.
+    ///
+    /// Whether the numbers at the edges belong to the range is left to
+    /// `get`, so the count always matches what iterating the range yields.
+    pub fn count(&self) -> Option<usize> {
+        let (start, end) = (self.start?, self.end?);
+        if self.get(0).is_none() {
+            return Some(0);
+        }
+
+        let steps = ((end - start) / self.step).floor().max(0.0);
+        if steps.is_nan() || steps >= usize::MAX as f64 {
+            return None;
+        }
+
+        let last = steps as usize;
+        if self.get(last).is_some() {
+            Some(last + 1)
+        } else {
+            Some(last)
+        }
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(start) = self.start {
+            write!(f, "{}", start)?;
+        }
+        write!(f, "{}", if self.inclusive { ".." } else { "..<" })?;
+        if let Some(end) = self.end {
+            write!(f, "{}", end)?;
+        }
+        if self.step != 1.0 {
+            write!(f, " step {}", self.step)?;
+        }
+        Ok(())
+    }
+}
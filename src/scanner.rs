@@ -1,11 +1,54 @@
 use crate::logger;
 
+/// A string literal whose `${` interpolation is currently being scanned.
+#[derive(Clone)]
+struct Interpolation {
+    /// Braces opened inside the interpolated expression and not yet closed.
+    braces: usize,
+    triple_quoted: bool,
+}
+
+/// Reserved words and the tokens they scan to.
+const KEYWORDS: [(&str, TokenType); 26] = [
+    ("and", TokenType::And),
+    ("break", TokenType::Break),
+    ("class", TokenType::Class),
+    ("const", TokenType::Const),
+    ("continue", TokenType::Continue),
+    ("else", TokenType::Else),
+    ("enum", TokenType::Enum),
+    ("false", TokenType::False),
+    ("for", TokenType::For),
+    ("fun", TokenType::Fun),
+    ("if", TokenType::If),
+    ("in", TokenType::In),
+    ("is", TokenType::Is),
+    ("match", TokenType::Match),
+    ("nil", TokenType::Nil),
+    ("or", TokenType::Or),
+    ("print", TokenType::Print),
+    ("return", TokenType::Return),
+    ("static", TokenType::Static),
+    ("super", TokenType::Super),
+    ("this", TokenType::This),
+    ("trait", TokenType::Trait),
+    ("true", TokenType::True),
+    ("var", TokenType::Var),
+    ("while", TokenType::While),
+    ("yield", TokenType::Yield),
+];
+
+#[derive(Clone)]
 pub struct Scanner<'a> {
     source: &'a str,
     start: usize,
     current: usize,
     line: usize,
     eof_emitted: bool,
+    interpolations: Vec<Interpolation>,
+    /// Byte range of the contents of the string literal just scanned.
+    contents: (usize, usize),
+    error: &'static str,
 }
 
 impl<'a> Scanner<'a> {
@@ -16,6 +59,9 @@ impl<'a> Scanner<'a> {
             current: 0,
             line: 1,
             eof_emitted: false,
+            interpolations: Vec::new(),
+            contents: (0, 0),
+            error: "",
         }
     }
 
@@ -36,14 +82,95 @@ impl<'a> Scanner<'a> {
         match self.advance() {
             '(' => Some(TokenType::LeftParen),
             ')' => Some(TokenType::RightParen),
-            '{' => Some(TokenType::LeftBrace),
-            '}' => Some(TokenType::RightBrace),
+            '{' => {
+                if let Some(interpolation) = self.interpolations.last_mut() {
+                    interpolation.braces += 1;
+                }
+                Some(TokenType::LeftBrace)
+            }
+            '}' => match self.interpolations.last_mut() {
+                Some(interpolation) if interpolation.braces == 0 => {
+                    let triple_quoted = interpolation.triple_quoted;
+                    self.interpolations.pop();
+                    Some(self.scan_string(triple_quoted))
+                }
+                Some(interpolation) => {
+                    interpolation.braces -= 1;
+                    Some(TokenType::RightBrace)
+                }
+                None => Some(TokenType::RightBrace),
+            },
+            '[' => Some(TokenType::LeftBracket),
+            ']' => Some(TokenType::RightBracket),
             ',' => Some(TokenType::Comma),
-            '.' => Some(TokenType::Dot),
-            '-' => Some(TokenType::Minus),
-            '+' => Some(TokenType::Plus),
+            '.' => {
+                if self.match_expected('.') {
+                    if self.match_expected('<') {
+                        Some(TokenType::DotDotLess)
+                    } else if self.match_expected('.') {
+                        Some(TokenType::DotDotDot)
+                    } else {
+                        Some(TokenType::DotDot)
+                    }
+                } else {
+                    Some(TokenType::Dot)
+                }
+            }
+            '-' => {
+                if self.match_expected('-') {
+                    Some(TokenType::MinusMinus)
+                } else if self.match_expected('=') {
+                    Some(TokenType::MinusEqual)
+                } else {
+                    Some(TokenType::Minus)
+                }
+            }
+            '+' => {
+                if self.match_expected('+') {
+                    Some(TokenType::PlusPlus)
+                } else if self.match_expected('=') {
+                    Some(TokenType::PlusEqual)
+                } else {
+                    Some(TokenType::Plus)
+                }
+            }
             ';' => Some(TokenType::Semicolon),
-            '*' => Some(TokenType::Star),
+            '*' => {
+                if self.match_expected('*') {
+                    Some(TokenType::StarStar)
+                } else if self.match_expected('=') {
+                    Some(TokenType::StarEqual)
+                } else {
+                    Some(TokenType::Star)
+                }
+            }
+            ':' => Some(TokenType::Colon),
+            '?' => {
+                if self.match_expected('?') {
+                    Some(TokenType::QuestionQuestion)
+                } else if self.match_expected('.') {
+                    Some(TokenType::QuestionDot)
+                } else {
+                    Some(TokenType::Question)
+                }
+            }
+            '%' => {
+                if self.match_expected('=') {
+                    Some(TokenType::PercentEqual)
+                } else {
+                    Some(TokenType::Percent)
+                }
+            }
+            '&' => Some(TokenType::Ampersand),
+            '|' => Some(TokenType::Pipe),
+            '^' => Some(TokenType::Caret),
+            '~' => {
+                if self.match_expected('/') {
+                    Some(TokenType::TildeSlash)
+                } else {
+                    Some(TokenType::Tilde)
+                }
+            }
             '!' => {
                 if self.match_expected('=') {
                     Some(TokenType::BangEqual)
@@ -54,6 +181,8 @@ impl<'a> Scanner<'a> {
             '=' => {
                 if self.match_expected('=') {
                     Some(TokenType::EqualEqual)
+                } else if self.match_expected('>') {
+                    Some(TokenType::Arrow)
                 } else {
                     Some(TokenType::Equal)
                 }
@@ -61,6 +190,8 @@ impl<'a> Scanner<'a> {
             '<' => {
                 if self.match_expected('=') {
                     Some(TokenType::LessEqual)
+                } else if self.match_expected('<') {
+                    Some(TokenType::LessLess)
                 } else {
                     Some(TokenType::Less)
                 }
@@ -68,6 +199,8 @@ impl<'a> Scanner<'a> {
             '>' => {
                 if self.match_expected('=') {
                     Some(TokenType::GreaterEqual)
+                } else if self.match_expected('>') {
+                    Some(TokenType::GreaterGreater)
                 } else {
                     Some(TokenType::Greater)
                 }
@@ -82,15 +215,38 @@ impl<'a> Scanner<'a> {
                         self.advance();
                     }
                     self.scan_token()
+                } else if self.match_expected('=') {
+                    Some(TokenType::SlashEqual)
                 } else {
                     Some(TokenType::Slash)
                 }
             }
-            '"' => Some(self.scan_string()),
-            c if c.is_digit(10) => Some(self.scan_number()),
+            '"' => {
+                let triple_quoted = self.source[self.current..].starts_with("\"\"");
+                if triple_quoted {
+                    self.current += 2;
+                    // A line break right after the opening quotes is not part of the string.
+                    if self.peek() == '\n' {
+                        self.line += 1;
+                        self.advance();
+                    }
+                }
+                Some(self.scan_string(triple_quoted))
+            }
+            'r' if self.peek() == '"' => Some(self.scan_raw_string()),
+            c if c.is_ascii_digit() => Some(self.scan_number(c)),
             c if self.is_id_start(c) => Some(self.scan_identifier()),
+            '#' => {
+                if !self.is_id_start(self.peek()) {
+                    return Some(self.error_token("Expect member name after '#'."));
+                }
+                while self.is_id_part(self.peek()) {
+                    self.advance();
+                }
+                Some(TokenType::PrivateName)
+            }
             '\0' => None,
-            _ => Some(TokenType::Error),
+            _ => Some(self.error_token("Unexpected character.")),
         }
     }
 
@@ -99,29 +255,10 @@ impl<'a> Scanner<'a> {
     }
 
     fn is_id_part(&self, c: char) -> bool {
-        self.is_id_start(c) || c.is_digit(10)
+        self.is_id_start(c) || c.is_ascii_digit()
     }
 
     fn scan_identifier(&mut self) -> TokenType {
-        let keywords = [
-            ("and", TokenType::And),
-            ("class", TokenType::Class),
-            ("else", TokenType::Else),
-            ("false", TokenType::False),
-            ("for", TokenType::For),
-            ("fun", TokenType::Fun),
-            ("if", TokenType::If),
-            ("nil", TokenType::Nil),
-            ("or", TokenType::Or),
-            ("print", TokenType::Print),
-            ("return", TokenType::Return),
-            ("super", TokenType::Super),
-            ("this", TokenType::This),
-            ("true", TokenType::True),
-            ("var", TokenType::Var),
-            ("while", TokenType::While),
-        ];
-
         loop {
             if !self.is_id_part(self.peek()) {
                 break;
@@ -132,7 +269,7 @@ impl<'a> Scanner<'a> {
 
         let text = &self.source[self.start..self.current];
 
-        for (keyword, token_type) in keywords.iter() {
+        for (keyword, token_type) in KEYWORDS.iter() {
             if text == *keyword {
                 return *token_type;
             }
@@ -141,76 +278,171 @@ impl<'a> Scanner<'a> {
         TokenType::Identifier
     }
 
-    fn scan_number(&mut self) -> TokenType {
-        loop {
-            if !self.peek().is_digit(10) {
-                break;
-            }
+    /// Scans a number literal whose first digit has been consumed: `123`,
+    /// `1.5`, `6.02e23`, `0xFF` or `0b1010`, any of them with `_` between
+    /// digits. The parser strips the separators and converts the lexeme.
+    fn scan_number(&mut self, first: char) -> TokenType {
+        if first == '0' && matches!(self.peek(), 'x' | 'X' | 'b' | 'B') {
+            let (radix, message) = match self.advance() {
+                'x' | 'X' => (16, "Expect hexadecimal digits after '0x'."),
+                _ => (2, "Expect binary digits after '0b'."),
+            };
+
+            return match self.scan_digits(radix, false) {
+                Ok(0) => self.error_token(message),
+                Ok(_) if self.peek().is_ascii_alphanumeric() => self.error_token(if radix == 2 {
+                    "Invalid digit in binary literal."
+                } else {
+                    "Invalid digit in hexadecimal literal."
+                }),
+                Ok(_) => TokenType::Number,
+                Err(message) => self.error_token(message),
+            };
+        }
 
-            self.advance();
+        if let Err(message) = self.scan_digits(10, true) {
+            return self.error_token(message);
         }
 
-        if self.peek() == '.' && self.peek_next().is_digit(10) {
+        if self.peek() == '.' && self.peek_next().is_ascii_digit() {
             self.advance();
 
-            loop {
-                if !self.peek().is_digit(10) {
-                    break;
-                }
+            if let Err(message) = self.scan_digits(10, false) {
+                return self.error_token(message);
+            }
+        }
+
+        if matches!(self.peek(), 'e' | 'E') {
+            self.advance();
 
+            if matches!(self.peek(), '+' | '-') {
                 self.advance();
             }
+
+            match self.scan_digits(10, false) {
+                Ok(0) => return self.error_token("Expect digits in exponent."),
+                Ok(_) => {}
+                Err(message) => return self.error_token(message),
+            }
         }
 
         TokenType::Number
     }
 
-    fn scan_string(&mut self) -> TokenType {
+    /// Consumes digits of `radix` and the `_` separators between them,
+    /// returning how many digits were read. `after_digit` is set when the
+    /// literal's first digit has already been consumed.
+    fn scan_digits(&mut self, radix: u32, after_digit: bool) -> Result<usize, &'static str> {
+        let mut count = 0;
+        let mut previous_is_digit = after_digit;
+
         loop {
-            if self.peek() == '"' {
+            let c = self.peek();
+
+            if c.is_digit(radix) {
+                count += 1;
+                previous_is_digit = true;
+            } else if c == '_' {
+                if !previous_is_digit {
+                    return Err("Digit separator '_' must be between digits.");
+                }
+                previous_is_digit = false;
+            } else {
                 break;
             }
 
-            if self.peek() == '\n' {
-                self.line += 1;
-            }
-
             self.advance();
         }
 
-        if self.is_at_end() {
-            return TokenType::Error;
+        if (count > 0 || after_digit) && !previous_is_digit {
+            return Err("Digit separator '_' must be between digits.");
         }
 
-        self.advance();
-
-        TokenType::String
+        Ok(count)
     }
 
-    fn skip_whitespace(&mut self) {
+    /// Scans string contents up to the closing quote or the next `${`. Escape
+    /// sequences are skipped over here and decoded by the parser.
+    fn scan_string(&mut self, triple_quoted: bool) -> TokenType {
+        let start = self.current;
+
         loop {
-            match self.peek() {
-                ' ' | '\r' | '\t' => {
+            if self.is_at_end() {
+                return self.error_token("Unterminated string.");
+            }
+
+            let rest = &self.source[self.current..];
+
+            if (triple_quoted && rest.starts_with("\"\"\""))
+                || (!triple_quoted && rest.starts_with('"'))
+            {
+                self.contents = (start, self.current);
+                self.current += if triple_quoted { 3 } else { 1 };
+                return TokenType::String;
+            }
+
+            if rest.starts_with("${") {
+                self.contents = (start, self.current);
+                self.current += 2;
+                self.interpolations.push(Interpolation {
+                    braces: 0,
+                    triple_quoted,
+                });
+                return TokenType::Interpolation;
+            }
+
+            match self.advance() {
+                '\\' => {
+                    if self.peek() == '\n' {
+                        self.line += 1;
+                    }
                     self.advance();
                 }
-                _ => break,
+                '\n' => self.line += 1,
+                _ => {}
             }
         }
     }
 
-    fn peek(&self) -> char {
-        match self.source[self.current..].chars().next() {
-            Some(c) => c,
-            None => '\0',
+    /// Scans `r"..."`, whose contents are taken verbatim.
+    fn scan_raw_string(&mut self) -> TokenType {
+        self.advance();
+        let start = self.current;
+
+        loop {
+            match self.advance() {
+                '\0' => return self.error_token("Unterminated string."),
+                '"' => break,
+                '\n' => self.line += 1,
+                _ => {}
+            }
+        }
+
+        self.contents = (start, self.current - 1);
+        TokenType::RawString
+    }
+
+    fn error_token(&mut self, message: &'static str) -> TokenType {
+        self.error = message;
+        TokenType::Error
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let ' ' | '\r' | '\t' = self.peek() {
+            self.advance();
         }
     }
 
+    fn peek(&self) -> char {
+        self.source[self.current..].chars().next().unwrap_or('\0')
+    }
+
     fn peek_next(&self) -> char {
         let current_len = self.peek().len_utf8();
-        match self.source[self.current + current_len..].chars().next() {
-            Some(c) => c,
-            None => '\0',
-        }
+        self.source[self.current + current_len..]
+            .chars()
+            .next()
+            .unwrap_or('\0')
     }
 
     fn match_expected(&mut self, expected: char) -> bool {
@@ -256,11 +488,13 @@ impl<'a> Scanner<'a> {
             ));
         }
 
-        let mut lexeme = &self.source[self.start..self.current];
-
-        if token_type == TokenType::String {
-            lexeme = &self.source[self.start + 1..self.current - 1];
-        }
+        let lexeme = match token_type {
+            TokenType::String | TokenType::Interpolation | TokenType::RawString => {
+                &self.source[self.contents.0..self.contents.1]
+            }
+            TokenType::Error => self.error,
+            _ => &self.source[self.start..self.current],
+        };
 
         Token {
             token_type,
@@ -270,6 +504,59 @@ impl<'a> Scanner<'a> {
     }
 }
 
+/// Decodes the escape sequences in the contents of a string literal.
+pub fn unescape(contents: &str) -> Result<String, String> {
+    let mut result = String::with_capacity(contents.len());
+    let mut chars = contents.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some('0') => result.push('\0'),
+            Some('\\') => result.push('\\'),
+            Some('"') => result.push('"'),
+            Some('$') => result.push('$'),
+            Some('u') => {
+                if chars.next() != Some('{') {
+                    return Err("Expect '{' after '\\u'.".to_string());
+                }
+
+                let digits: String = chars
+                    .as_str()
+                    .chars()
+                    .take_while(char::is_ascii_hexdigit)
+                    .collect();
+                // Hex digits are ASCII, one byte each.
+                chars = chars.as_str()[digits.len()..].chars();
+                if chars.next() != Some('}') {
+                    return Err(format!("Invalid Unicode escape '\\u{{{}'.", digits));
+                }
+
+                let decoded = u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .filter(|_| (1..=6).contains(&digits.len()))
+                    .and_then(char::from_u32);
+
+                match decoded {
+                    Some(c) => result.push(c),
+                    None => return Err(format!("Invalid Unicode escape '\\u{{{}}}'.", digits)),
+                }
+            }
+            Some(c) => return Err(format!("Invalid escape sequence '\\{}'.", c)),
+            None => return Err("Unterminated escape sequence.".to_string()),
+        }
+    }
+
+    Ok(result)
+}
+
 impl<'a> Iterator for Scanner<'a> {
     type Item = Token<'a>;
 
@@ -285,63 +572,102 @@ pub enum TokenType {
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
     Comma,
     Dot,
+    DotDot,
+    DotDotLess,
+    DotDotDot,
+    Colon,
+    Question,
+    QuestionQuestion,
+    QuestionDot,
     Minus,
+    MinusMinus,
+    MinusEqual,
     Plus,
+    PlusPlus,
+    PlusEqual,
     Semicolon,
     Star,
+    StarStar,
+    StarEqual,
     Slash,
+    SlashEqual,
+    TildeSlash,
+    Percent,
+    PercentEqual,
+    Ampersand,
+    Pipe,
+    Caret,
+    Tilde,
+    LessLess,
+    GreaterGreater,
     Bang,
     BangEqual,
     Equal,
     EqualEqual,
+    Arrow,
     Greater,
     GreaterEqual,
     Less,
     LessEqual,
     Identifier,
+    /// A class member name starting with `#`, visible only inside the class.
+    PrivateName,
     String,
+    /// A string segment followed by an interpolated `${` expression.
+    Interpolation,
+    RawString,
     Number,
     And,
+    Break,
     Class,
+    Const,
+    Continue,
     Else,
+    Enum,
     False,
     For,
     Fun,
     If,
+    In,
+    Is,
+    Match,
     Nil,
     Or,
     Print,
     Return,
+    Static,
     Super,
     This,
+    Trait,
     True,
     Var,
     While,
+    Yield,
     Error,
     Eof,
 }
 
+impl TokenType {
+    /// Whether this is a reserved word. Reserved words can still name
+    /// properties and methods.
+    pub fn is_keyword(&self) -> bool {
+        KEYWORDS.iter().any(|(_, token_type)| token_type == self)
+    }
+}
+
 impl Clone for TokenType {
     fn clone(&self) -> TokenType {
         *self
     }
 }
 
-#[derive(Debug, Copy)]
+#[derive(Debug, Clone, Copy)]
 pub struct Token<'a> {
     pub token_type: TokenType,
     pub lexeme: &'a str,
     pub line: usize,
 }
-
-impl<'a> Clone for Token<'a> {
-    fn clone(&self) -> Token<'a> {
-        Token {
-            token_type: self.token_type.clone(),
-            lexeme: self.lexeme,
-            line: self.line,
-        }
-    }
-}
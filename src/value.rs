@@ -1,9 +1,14 @@
 use std::{cell::RefCell, fmt, rc::Rc};
 
 use crate::{
-    class::{BoundMethod, Class, Instance},
+    class::{BoundMethod, Class, Instance, Trait},
     closure::Closure,
+    enumeration::{Enum, Variant},
+    fiber::Fiber,
     function::NativeFunction,
+    iterator::NativeIterator,
+    map::Map,
+    range::Range,
     upvalue::Upvalue,
 };
 
@@ -17,8 +22,20 @@ pub enum Value {
     Closure(Rc<Closure>),
     Upvalue(Rc<RefCell<Upvalue>>),
     Class(Rc<RefCell<Class>>),
+    Trait(Rc<RefCell<Trait>>),
     Instance(Rc<RefCell<Instance>>),
     BoundMethod(Rc<RefCell<BoundMethod>>),
+    Fiber(Rc<RefCell<Fiber>>),
+    List(Rc<RefCell<Vec<Value>>>),
+    Map(Rc<RefCell<Map>>),
+    /// An immutable group of values, such as several values returned at once.
+    Tuple(Rc<Vec<Value>>),
+    Enum(Rc<Enum>),
+    /// A variant of an enum, or the constructor of one that carries
+    /// associated values.
+    Variant(Rc<Variant>),
+    Range(Rc<Range>),
+    Iterator(Rc<RefCell<NativeIterator>>),
 }
 
 impl Value {
@@ -30,6 +47,41 @@ impl Value {
         }
     }
 
+    /// The equality `==` tests. Tuples and enum variants compare by their
+    /// contents; other objects are only equal to themselves.
+    pub fn equals(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Nil, Value::Nil) => true,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Tuple(a), Value::Tuple(b)) => {
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a.equals(b))
+            }
+            (Value::Variant(a), Value::Variant(b)) => {
+                Rc::ptr_eq(a, b)
+                    || a.same_variant(b)
+                        && match (&a.values, &b.values) {
+                            (Some(a), Some(b)) => a.iter().zip(b.iter()).all(|(a, b)| a.equals(b)),
+                            _ => false,
+                        }
+            }
+            (Value::NativeFunction(a), Value::NativeFunction(b)) => Rc::ptr_eq(a, b),
+            (Value::Closure(a), Value::Closure(b)) => Rc::ptr_eq(a, b),
+            (Value::Class(a), Value::Class(b)) => Rc::ptr_eq(a, b),
+            (Value::Trait(a), Value::Trait(b)) => Rc::ptr_eq(a, b),
+            (Value::Instance(a), Value::Instance(b)) => Rc::ptr_eq(a, b),
+            (Value::BoundMethod(a), Value::BoundMethod(b)) => Rc::ptr_eq(a, b),
+            (Value::Fiber(a), Value::Fiber(b)) => Rc::ptr_eq(a, b),
+            (Value::List(a), Value::List(b)) => Rc::ptr_eq(a, b),
+            (Value::Map(a), Value::Map(b)) => Rc::ptr_eq(a, b),
+            (Value::Enum(a), Value::Enum(b)) => Rc::ptr_eq(a, b),
+            (Value::Range(a), Value::Range(b)) => a == b,
+            (Value::Iterator(a), Value::Iterator(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+
     pub fn type_name(&self) -> &str {
         match self {
             Value::Bool(_) => "bool",
@@ -40,8 +92,17 @@ impl Value {
             Value::Closure(_) => "closure",
             Value::Upvalue(_) => "upvalue",
             Value::Class(_) => "class",
+            Value::Trait(_) => "trait",
             Value::Instance(_) => "instance",
             Value::BoundMethod(_) => "bound method",
+            Value::Fiber(_) => "fiber",
+            Value::List(_) => "list",
+            Value::Map(_) => "map",
+            Value::Tuple(_) => "tuple",
+            Value::Enum(_) => "enum",
+            Value::Variant(_) => "variant",
+            Value::Range(_) => "range",
+            Value::Iterator(_) => "iterator",
         }
     }
 
@@ -56,6 +117,102 @@ impl Value {
     pub fn bound_method(bound_method: BoundMethod) -> Self {
         Value::BoundMethod(Rc::new(RefCell::new(bound_method)))
     }
+
+    pub fn fiber(fiber: Fiber) -> Self {
+        Value::Fiber(Rc::new(RefCell::new(fiber)))
+    }
+
+    pub fn list(values: Vec<Value>) -> Self {
+        Value::List(Rc::new(RefCell::new(values)))
+    }
+
+    pub fn map(map: Map) -> Self {
+        Value::Map(Rc::new(RefCell::new(map)))
+    }
+
+    pub fn tuple(values: Vec<Value>) -> Self {
+        Value::Tuple(Rc::new(values))
+    }
+
+    pub fn iterator(iterator: NativeIterator) -> Self {
+        Value::Iterator(Rc::new(RefCell::new(iterator)))
+    }
+}
+
+impl Value {
+    /// Writes the elements of a list, tuple or variant, separated by commas.
+    /// `lists` holds the lists and maps being written, to cut cycles short.
+    fn write_elements(
+        f: &mut fmt::Formatter,
+        values: &[Value],
+        lists: &mut Vec<*const ()>,
+    ) -> fmt::Result {
+        for (index, value) in values.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            value.write(f, lists)?;
+        }
+        Ok(())
+    }
+
+    fn write(&self, f: &mut fmt::Formatter, lists: &mut Vec<*const ()>) -> fmt::Result {
+        match self {
+            Self::List(list) => {
+                let key = Rc::as_ptr(list) as *const ();
+                if lists.contains(&key) {
+                    return write!(f, "[...]");
+                }
+
+                lists.push(key);
+                write!(f, "[")?;
+                Self::write_elements(f, &list.borrow(), lists)?;
+                lists.pop();
+                write!(f, "]")
+            }
+            Self::Map(map) => {
+                let key = Rc::as_ptr(map) as *const ();
+                if lists.contains(&key) {
+                    return write!(f, "[...]");
+                }
+                if map.borrow().is_empty() {
+                    return write!(f, "[:]");
+                }
+
+                lists.push(key);
+                write!(f, "[")?;
+                for (index, (key, value)) in map.borrow().entries().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: ", key)?;
+                    value.write(f, lists)?;
+                }
+                lists.pop();
+                write!(f, "]")
+            }
+            Self::Tuple(values) => {
+                write!(f, "(")?;
+                Self::write_elements(f, values, lists)?;
+                match values.len() {
+                    1 => write!(f, ",)"),
+                    _ => write!(f, ")"),
+                }
+            }
+            Self::Variant(variant) => {
+                write!(f, "{}.{}", variant.definition.name, variant.name())?;
+                match &variant.values {
+                    Some(values) if !values.is_empty() => {
+                        write!(f, "(")?;
+                        Self::write_elements(f, values, lists)?;
+                        write!(f, ")")
+                    }
+                    _ => Ok(()),
+                }
+            }
+            _ => write!(f, "{}", self),
+        }
+    }
 }
 
 impl fmt::Display for Value {
@@ -74,6 +231,7 @@ impl fmt::Display for Value {
                 upvalue.borrow().closed
             ),
             Self::Class(class) => write!(f, "{}", class.borrow().name),
+            Self::Trait(r#trait) => write!(f, "{}", r#trait.borrow().name),
             Self::Instance(instance) => {
                 write!(f, "instance {}", instance.borrow().class.borrow().name)
             }
@@ -84,6 +242,13 @@ impl fmt::Display for Value {
                     bound_method.borrow().method.function.name
                 )
             }
+            Self::Fiber(fiber) => write!(f, "fiber {}", fiber.borrow().closure.function.name),
+            Self::List(_) | Self::Map(_) | Self::Tuple(_) | Self::Variant(_) => {
+                self.write(f, &mut Vec::new())
+            }
+            Self::Enum(enumeration) => write!(f, "{}", enumeration.name()),
+            Self::Range(range) => write!(f, "{}", range),
+            Self::Iterator(_) => write!(f, "iterator"),
         }
     }
 }
@@ -1,14 +1,24 @@
 use crate::call_frame::CallFrame;
-use crate::chunk::{Instruction, OpCode};
-use crate::class::{BoundMethod, Class, Instance};
+use crate::chunk::{Instruction, OpCode, SwitchKey};
+use crate::class::{member_name, split_private_key, BoundMethod, Class, Instance, Trait};
 use crate::closure::Closure;
-use crate::function::NativeFunction;
-use crate::native_functions::clock;
+use crate::enumeration::Variant;
+use crate::error::ErrorKind;
+use crate::fiber::{Fiber, FiberState};
+use crate::function::{Arity, Function, NativeError, NativeFn, NativeFunction};
+use crate::iterator::{IteratorSource, NativeIterator};
+use crate::map::Map;
+use crate::native_functions::{
+    arity, class_of, clock, delete_field, fiber, fields, get_field, has_field, implements, len,
+    methods, name, push, set_field, str, superclass_of, type_of,
+};
+use crate::range::Range;
 use crate::upvalue::Upvalue;
 use crate::value::Value;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::{self, Write};
+use std::mem;
 use std::rc::Rc;
 
 #[derive(Debug)]
@@ -32,7 +42,9 @@ pub type InterpretResult = Result<(), InterpretError>;
 
 pub type CallFrameStack = Vec<CallFrame>;
 
-pub fn format_stack(stack: &Vec<Value>) -> String {
+const PRELUDE: &str = include_str!("prelude.lox");
+
+pub fn format_stack(stack: &[Value]) -> String {
     stack
         .iter()
         .map(|value| format!("{}", value))
@@ -44,108 +56,204 @@ pub fn format_stack(stack: &Vec<Value>) -> String {
 pub struct VM {
     stack: Vec<Value>,
     globals: HashMap<String, Value>,
+    /// Globals declared with `const`, which can't be assigned or redefined.
+    constants: HashSet<String>,
     call_frame_stack: CallFrameStack,
     open_upvalues: Vec<Rc<Upvalue>>,
+    fibers: Vec<Rc<RefCell<Fiber>>>,
+    reentrant_calls: usize,
+    /// Lists and instances whose conversion to a string is in progress, so a
+    /// value that contains or prints itself doesn't recurse forever.
+    converting: Vec<*const ()>,
+    /// A runtime error raised while a native function was calling back into
+    /// Lox, re-raised once the native returns.
+    native_error: Option<InterpretError>,
+    error_classes: HashMap<ErrorKind, Rc<RefCell<Class>>>,
+    /// The error value created by the most recent runtime error.
+    error: Option<Value>,
     debug: bool,
 }
 
+impl Default for VM {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl VM {
     pub fn new() -> VM {
-        let mut globals = HashMap::new();
-        globals.insert(
-            "clock".to_string(),
-            Value::NativeFunction(Rc::new(NativeFunction::new("clock", clock))),
-        );
-
         let debug = std::env::var("DEBUG")
             .map(|level| level == "debug")
             .unwrap_or(false);
 
-        VM {
+        let mut vm = VM {
             stack: Vec::new(),
-            globals,
+            globals: HashMap::new(),
+            constants: HashSet::new(),
             call_frame_stack: CallFrameStack::new(),
             open_upvalues: Vec::new(),
+            fibers: Vec::new(),
+            reentrant_calls: 0,
+            converting: Vec::new(),
+            native_error: None,
+            error_classes: HashMap::new(),
+            error: None,
             debug,
+        };
+
+        vm.define_native_with_arity("clock", Arity::exact(0), clock);
+        vm.define_native_with_arity("Fiber", Arity::exact(1), fiber);
+        vm.define_native_with_arity("len", Arity::exact(1), len);
+        vm.define_native_with_arity("push", Arity::at_least(2), push);
+        vm.define_native_with_arity("str", Arity::exact(1), str);
+        vm.define_native_with_arity("typeOf", Arity::exact(1), type_of);
+        vm.define_native_with_arity("classOf", Arity::exact(1), class_of);
+        vm.define_native_with_arity("hasField", Arity::exact(2), has_field);
+        vm.define_native_with_arity("getField", Arity::exact(2), get_field);
+        vm.define_native_with_arity("setField", Arity::exact(3), set_field);
+        vm.define_native_with_arity("deleteField", Arity::exact(2), delete_field);
+        vm.define_native_with_arity("fields", Arity::exact(1), fields);
+        vm.define_native_with_arity("methods", Arity::exact(1), methods);
+        vm.define_native_with_arity("superclassOf", Arity::exact(1), superclass_of);
+        vm.define_native_with_arity("implements", Arity::exact(2), implements);
+        vm.define_native_with_arity("arity", Arity::exact(1), arity);
+        vm.define_native_with_arity("name", Arity::exact(1), name);
+
+        crate::run::run(PRELUDE.to_string(), &mut vm).expect("Failed to run prelude");
+        vm.stack.clear();
+
+        for kind in ErrorKind::ALL {
+            if let Some(Value::Class(class)) = vm.globals.get(kind.class_name()) {
+                vm.error_classes.insert(kind, Rc::clone(class));
+            }
         }
+
+        vm
+    }
+
+    /// Makes `function` callable from scripts as the global `name`, with any
+    /// number of arguments.
+    pub fn define_native(&mut self, name: &str, function: NativeFn) {
+        self.define_native_with_arity(name, Arity::at_least(0), function);
+    }
+
+    /// Like `define_native`, but calls with an argument count outside `arity`
+    /// are rejected before `function` runs.
+    pub fn define_native_with_arity(&mut self, name: &str, arity: Arity, function: NativeFn) {
+        self.globals.insert(
+            name.to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new(name, arity, function))),
+        );
     }
 
     pub fn interpret(&mut self, frame: CallFrame) -> InterpretResult {
         self.call_frame_stack.push(frame);
-        self.run()
+
+        let result = self.run(0, 0);
+        if result.is_err() {
+            self.abandon_fibers();
+        }
+
+        result
+    }
+
+    /// Executes instructions until the frame stack of the context identified by
+    /// `base_fibers` unwinds back to `base_depth` frames.
+    ///
+    /// A runtime error inside a fiber resumed with `try` by this run finishes
+    /// that fiber, and its resumer continues with the error value.
+    fn run(&mut self, base_depth: usize, base_fibers: usize) -> InterpretResult {
+        loop {
+            let error = match self.execute(base_depth, base_fibers) {
+                Ok(()) => return Ok(()),
+                Err(error) => error,
+            };
+
+            let catcher = self
+                .fibers
+                .iter()
+                .rposition(|fiber| fiber.borrow().catches_errors);
+
+            match catcher {
+                Some(index)
+                    if index >= base_fibers
+                        && self.fibers[index].borrow().reentrant_calls == self.reentrant_calls =>
+                {
+                    let value = self.error_value(&error);
+
+                    while self.fibers.len() > index {
+                        if let Some(fiber) = self.fibers.pop() {
+                            self.switch_context(&fiber);
+                            fiber.borrow_mut().state = FiberState::Done;
+                        }
+                    }
+
+                    self.push_stack(value);
+                }
+                _ => return Err(error),
+            }
+        }
     }
 
-    fn run(&mut self) -> InterpretResult {
+    fn execute(&mut self, base_depth: usize, base_fibers: usize) -> InterpretResult {
         loop {
             let instruction = match self.next_instruction() {
                 Some(instr) => instr.clone(),
-                None => return self.runtime_error("No more instructions", 0),
+                None => return self.runtime_error(ErrorKind::Error, "No more instructions", 0),
             };
 
             let line = instruction.line;
             match &instruction.op_code {
                 OpCode::Return => match self.call_frame_stack.pop() {
                     Some(frame) => {
-                        if self.call_frame_stack.is_empty() {
+                        if self.call_frame_stack.is_empty() && self.fibers.is_empty() {
                             return Ok(());
                         }
 
                         let result = self.pop_stack(line)?;
                         self.close_upvalues(frame.slot_start)?;
                         self.stack.truncate(frame.slot_start);
-                        self.push_stack(result);
-                    }
-                    None => return self.runtime_error("No call frame to return from", line),
-                },
-                OpCode::Call(arg_count) => {
-                    let arg_count = *arg_count;
-
-                    let callee_index = self.stack.len() - arg_count - 1;
-                    let callee = self.stack[callee_index].clone();
-
-                    match callee {
-                        Value::Closure(closure) => {
-                            self.call_closure(closure, arg_count, line, callee_index)?;
-                        }
 
-                        Value::NativeFunction(native) => {
-                            let args_start = callee_index + 1;
-                            let args: Vec<Value> = self.stack[args_start..].to_vec();
-
-                            self.stack.truncate(callee_index);
-
-                            let result = (native.function)(args);
+                        if self.call_frame_stack.is_empty() {
+                            self.suspend_fiber(result, FiberState::Done, line)?;
+                        } else {
                             self.push_stack(result);
                         }
 
-                        Value::Class(class) => {
-                            self.stack[callee_index] =
-                                Value::instance(Instance::new(class.clone()));
-
-                            if let Some(initializer) = class.borrow().methods.get("init") {
-                                self.call_closure(
-                                    initializer.clone(),
-                                    arg_count,
-                                    line,
-                                    callee_index,
-                                )?;
-                            } else if arg_count != 0 {
-                                return self.runtime_error(
-                                    &format!("Expected 0 arguments but got {}", arg_count),
-                                    line,
-                                );
-                            }
+                        if self.call_frame_stack.len() == base_depth
+                            && self.fibers.len() == base_fibers
+                        {
+                            return Ok(());
                         }
-
-                        Value::BoundMethod(bound_method) => {
-                            let closure = Rc::clone(&bound_method.borrow().method);
-                            self.stack[callee_index] =
-                                Value::Instance(bound_method.borrow().receiver.clone());
-                            self.call_closure(closure, arg_count, line, callee_index)?;
+                    }
+                    None => {
+                        return self.runtime_error(
+                            ErrorKind::Error,
+                            "No call frame to return from",
+                            line,
+                        )
+                    }
+                },
+                OpCode::Call(arg_count) => {
+                    let callee_index = self.stack.len() - arg_count - 1;
+                    self.call(callee_index, *arg_count, line)?;
+                }
+                OpCode::CallSpread => {
+                    let arguments = match self.pop_stack(line)? {
+                        Value::List(list) => list.borrow().clone(),
+                        value => {
+                            return self.runtime_error(
+                                ErrorKind::TypeError,
+                                &format!("Expected argument list, got {}", value.type_name()),
+                                line,
+                            );
                         }
+                    };
 
-                        _ => return self.runtime_error("Cannot call non-function value", line),
-                    }
+                    let callee_index = self.stack.len();
+                    let arg_count = arguments.len();
+                    self.stack.extend(arguments);
+                    self.call(callee_index - 1, arg_count, line)?;
                 }
                 OpCode::Closure(function) => {
                     let mut closure = Closure::new(function.clone());
@@ -174,7 +282,11 @@ impl VM {
                                 if let Some(frame) = self.call_frame_stack.last_mut() {
                                     closure.upvalues.push(frame.closure.upvalues[index].clone());
                                 } else {
-                                    return self.runtime_error("No call frame found", line);
+                                    return self.runtime_error(
+                                        ErrorKind::Error,
+                                        "No call frame found",
+                                        line,
+                                    );
                                 }
                             }
                         }
@@ -187,83 +299,189 @@ impl VM {
                 }
                 OpCode::Negate => match self.stack.pop() {
                     Some(Value::Number(value)) => self.push_stack(Value::Number(-value)),
-                    Some(_) => return self.runtime_error("Cannot negate non-number value", line),
-                    None => return self.runtime_error("Not enough values to negate", line),
+                    Some(value) => {
+                        if !self.call_operator("__neg__", &[value], line)? {
+                            return self.runtime_error(
+                                ErrorKind::TypeError,
+                                "Cannot negate non-number value",
+                                line,
+                            );
+                        }
+                    }
+                    None => {
+                        return self.runtime_error(
+                            ErrorKind::Error,
+                            "Not enough values to negate",
+                            line,
+                        )
+                    }
                 },
                 OpCode::Add => match (self.pop_stack(line)?, self.pop_stack(line)?) {
                     (Value::Number(b), Value::Number(a)) => self.push_stack(Value::Number(a + b)),
                     (Value::String(b), Value::String(a)) => {
                         self.push_stack(Value::String(Rc::new(format!("{}{}", a, b))))
                     }
-                    (a, b) => {
-                        return self.runtime_error(
-                            &format!(
-                                "Operands must be numbers or strings, found: {} and {}",
-                                a, b
-                            ),
-                            line,
-                        );
+                    (b, a) => {
+                        if !self.call_operator("__add__", &[a.clone(), b.clone()], line)? {
+                            match self.concatenate_converted(&a, &b, line)? {
+                                Some(string) => self.push_stack(string),
+                                None => {
+                                    return self.runtime_error(
+                                        ErrorKind::TypeError,
+                                        &format!(
+                                            "Operands must be numbers or strings, found: {} and {}",
+                                            a, b
+                                        ),
+                                        line,
+                                    );
+                                }
+                            }
+                        }
                     }
                 },
-                OpCode::Subtract => self.binary_op(|a, b| Ok(Value::Number(a - b)), line)?,
-                OpCode::Multiply => self.binary_op(|a, b| Ok(Value::Number(a * b)), line)?,
-                OpCode::Divide => {
-                    if let (Value::Number(b), Value::Number(a)) =
-                        (self.pop_stack(line)?, self.pop_stack(line)?)
-                    {
+                OpCode::Stringify => {
+                    let value = self.pop_stack(line)?;
+                    let string = self.stringify(value, line)?;
+                    self.push_stack(Value::String(string));
+                }
+                OpCode::Subtract => {
+                    self.binary_op(|a, b| Ok(Value::Number(a - b)), "__sub__", line)?
+                }
+                OpCode::Multiply => {
+                    self.binary_op(|a, b| Ok(Value::Number(a * b)), "__mul__", line)?
+                }
+                OpCode::Divide => match (self.pop_stack(line)?, self.pop_stack(line)?) {
+                    (Value::Number(b), Value::Number(a)) => {
                         if b == 0.0 {
-                            return self.runtime_error("Division by zero", line);
+                            return self.runtime_error(
+                                ErrorKind::ArithmeticError,
+                                "Division by zero",
+                                line,
+                            );
                         }
                         self.push_stack(Value::Number(a / b));
+                    }
+                    (b, a) => {
+                        if !self.call_operator("__div__", &[a, b], line)? {
+                            return self.runtime_error(
+                                ErrorKind::TypeError,
+                                "Operands must be numbers",
+                                line,
+                            );
+                        }
+                    }
+                },
+                OpCode::IntegerDivide | OpCode::Modulo => {
+                    let (b, a) = (self.pop_stack(line)?, self.pop_stack(line)?);
+                    if let (Value::Number(b), Value::Number(a)) = (&b, &a) {
+                        let (a, b) = (*a, *b);
+                        if b == 0.0 {
+                            return self.runtime_error(
+                                ErrorKind::ArithmeticError,
+                                "Division by zero",
+                                line,
+                            );
+                        }
+
+                        // Both round towards zero, so `a == (a ~/ b) * b + a % b`.
+                        let result = match instruction.op_code {
+                            OpCode::IntegerDivide => (a / b).trunc(),
+                            _ => a % b,
+                        };
+                        self.push_stack(Value::Number(result));
                     } else {
-                        return self.runtime_error("Operands must be numbers", line);
+                        let method = match instruction.op_code {
+                            OpCode::IntegerDivide => "__intdiv__",
+                            _ => "__mod__",
+                        };
+                        if !self.call_operator(method, &[a, b], line)? {
+                            return self.runtime_error(
+                                ErrorKind::TypeError,
+                                "Operands must be numbers",
+                                line,
+                            );
+                        }
                     }
                 }
+                OpCode::Power => {
+                    self.binary_op(|a, b| Ok(Value::Number(a.powf(b))), "__pow__", line)?
+                }
+                OpCode::BitAnd => self.integer_op(|a, b| Ok(a & b), line)?,
+                OpCode::BitOr => self.integer_op(|a, b| Ok(a | b), line)?,
+                OpCode::BitXor => self.integer_op(|a, b| Ok(a ^ b), line)?,
+                OpCode::ShiftLeft => {
+                    self.integer_op(|a, b| Self::shift_amount(b).map(|b| a << b), line)?
+                }
+                OpCode::ShiftRight => {
+                    self.integer_op(|a, b| Self::shift_amount(b).map(|b| a >> b), line)?
+                }
+                OpCode::BitNot => match self.pop_stack(line)? {
+                    Value::Number(value) => match Self::to_integer(value) {
+                        Some(value) => self.push_stack(Value::Number(!value as f64)),
+                        None => {
+                            return self.runtime_error(
+                                ErrorKind::TypeError,
+                                &format!("Operand must be an integer, got {}", value),
+                                line,
+                            )
+                        }
+                    },
+                    _ => {
+                        return self.runtime_error(
+                            ErrorKind::TypeError,
+                            "Operand must be a number",
+                            line,
+                        )
+                    }
+                },
                 OpCode::Not => {
                     let value = self.pop_stack(line)?;
                     self.push_stack(Value::Bool(value.is_falsey()));
                 }
-                OpCode::Equal => match (self.pop_stack(line)?, self.pop_stack(line)?) {
-                    (Value::Number(a), Value::Number(b)) => {
-                        self.push_stack(Value::Bool(a == b));
+                OpCode::Equal => {
+                    let b = self.pop_stack(line)?;
+                    let a = self.pop_stack(line)?;
+                    let overloaded = matches!(a, Value::Instance(_))
+                        && self.call_operator("__eq__", &[a.clone(), b.clone()], line)?;
+                    if !overloaded {
+                        self.push_stack(Value::Bool(a.equals(&b)));
                     }
-                    (Value::Bool(a), Value::Bool(b)) => {
-                        self.push_stack(Value::Bool(a == b));
-                    }
-                    (Value::Nil, Value::Nil) => {
-                        self.push_stack(Value::Bool(true));
-                    }
-                    (Value::String(a), Value::String(b)) => {
-                        self.push_stack(Value::Bool(a == b));
-                    }
-                    (_, _) => {
-                        self.push_stack(Value::Bool(false));
-                    }
-                },
-                OpCode::Greater => self.binary_op(|a, b| Ok(Value::Bool(a > b)), line)?,
-                OpCode::Less => self.binary_op(|a, b| Ok(Value::Bool(a < b)), line)?,
+                }
+                OpCode::Greater => self.binary_op(|a, b| Ok(Value::Bool(a > b)), "__gt__", line)?,
+                OpCode::Less => self.binary_op(|a, b| Ok(Value::Bool(a < b)), "__lt__", line)?,
                 OpCode::Print => {
-                    let stdout = io::stdout();
-                    let mut handle = stdout.lock();
                     let value = self.pop_stack(line)?;
+                    let text = self.display(&value, line)?;
 
-                    match value {
-                        Value::Closure(closure) => {
-                            writeln!(handle, "{}", closure.function).unwrap()
-                        }
-                        _ => writeln!(handle, "{}", value).unwrap(),
-                    }
+                    let stdout = io::stdout();
+                    let mut handle = stdout.lock();
+                    writeln!(handle, "{}", text).unwrap();
                 }
-                OpCode::DefineGlobal(name) => {
+                OpCode::DefineGlobal(name) | OpCode::DefineConstant(name) => {
+                    if self.constants.contains(name) {
+                        return self.runtime_error(
+                            ErrorKind::NameError,
+                            &format!("Cannot redefine constant '{}'", name),
+                            line,
+                        );
+                    }
+
                     let value = self.pop_stack(line)?;
                     self.globals.insert(name.clone(), value);
+
+                    if let OpCode::DefineConstant(_) = instruction.op_code {
+                        self.constants.insert(name.clone());
+                    }
                 }
                 OpCode::GetGlobal(name) => {
                     let value = match self.globals.get(name) {
                         Some(val) => val,
                         None => {
-                            return self
-                                .runtime_error(&format!("Undefined variable '{}'", name), line);
+                            return self.runtime_error(
+                                ErrorKind::NameError,
+                                &format!("Undefined variable '{}'", name),
+                                line,
+                            );
                         }
                     };
 
@@ -271,7 +489,18 @@ impl VM {
                 }
                 OpCode::SetGlobal(name) => {
                     if !self.globals.contains_key(&name[..]) {
-                        return self.runtime_error(&format!("Undefined variable '{}'", name), line);
+                        return self.runtime_error(
+                            ErrorKind::NameError,
+                            &format!("Undefined variable '{}'", name),
+                            line,
+                        );
+                    }
+                    if self.constants.contains(name) {
+                        return self.runtime_error(
+                            ErrorKind::NameError,
+                            &format!("Cannot assign to constant '{}'", name),
+                            line,
+                        );
                     }
                     let value = self.peek_stack(line)?;
                     self.globals.insert(name.clone(), value);
@@ -280,6 +509,7 @@ impl VM {
                     let absolute_index = self.to_absolute_index(*local);
                     if absolute_index >= self.stack.len() {
                         return self.runtime_error(
+                            ErrorKind::Error,
                             &format!("Invalid local variable index {}", local),
                             line,
                         );
@@ -300,6 +530,7 @@ impl VM {
                     let absolute_index = self.to_absolute_index(*index);
                     if absolute_index >= self.stack.len() {
                         return self.runtime_error(
+                            ErrorKind::Error,
                             &format!("Invalid local variable index {}", index),
                             line,
                         );
@@ -311,6 +542,24 @@ impl VM {
                 OpCode::Pop => {
                     self.pop_stack(line)?;
                 }
+                OpCode::Dup(depth) => {
+                    let value = self.peek_stack_at(*depth, line)?;
+                    self.push_stack(value);
+                }
+                OpCode::Bury(depth) => {
+                    // Moves the top value down so `depth` values sit above it.
+                    let value = self.pop_stack(line)?;
+                    match self.stack.len().checked_sub(*depth) {
+                        Some(index) => self.stack.insert(index, value),
+                        None => {
+                            return self.runtime_error(
+                                ErrorKind::Error,
+                                &format!("Cannot bury value {} slots deep", depth),
+                                line,
+                            )
+                        }
+                    }
+                }
                 OpCode::JumpIfFalse(offset) => {
                     let effective_offset = if self.peek_stack(line)?.is_falsey() {
                         *offset
@@ -320,6 +569,16 @@ impl VM {
 
                     self.offset(effective_offset);
                 }
+                OpCode::JumpIfNil(offset) => {
+                    if let Value::Nil = self.peek_stack(line)? {
+                        self.offset(*offset);
+                    }
+                }
+                OpCode::JumpIfNotNil(offset) => {
+                    if !matches!(self.peek_stack(line)?, Value::Nil) {
+                        self.offset(*offset);
+                    }
+                }
                 OpCode::Jump(offset) => {
                     let offset = *offset;
                     self.offset(offset);
@@ -332,33 +591,56 @@ impl VM {
                     let upvalue = self
                         .call_frame_stack
                         .last()
-                        .map(|frame| frame.closure.upvalues.get(*index))
-                        .flatten();
+                        .and_then(|frame| frame.closure.upvalues.get(*index));
 
                     if let Some(upvalue) = upvalue {
                         let value = upvalue.get_value();
                         self.push_stack(value);
                     } else {
-                        return self
-                            .runtime_error(&format!("Invalid upvalue index {}", index), line);
+                        return self.runtime_error(
+                            ErrorKind::Error,
+                            &format!("Invalid upvalue index {}", index),
+                            line,
+                        );
                     }
                 }
                 OpCode::SetUpvalue(index) => {
                     let value = self.peek_stack(line)?;
                     let frame = match self.call_frame_stack.last_mut() {
                         Some(frame) => frame,
-                        None => return self.runtime_error("No call frame found", line),
+                        None => {
+                            return self.runtime_error(
+                                ErrorKind::Error,
+                                "No call frame found",
+                                line,
+                            )
+                        }
                     };
 
-                    if let Some(upvalue) = frame.closure.upvalues.get(*index) {
-                        upvalue.set_value(value);
+                    if let Some(upvalue) = frame.closure.upvalues.get(*index).cloned() {
+                        upvalue.set_value(value.clone());
+
+                        // The captured slot may still be live on this stack.
+                        if self
+                            .open_upvalues
+                            .iter()
+                            .any(|open| Rc::ptr_eq(open, &upvalue))
+                        {
+                            if let Some(stack_index) = *upvalue.stack_index.borrow() {
+                                self.stack[stack_index] = value;
+                            }
+                        }
                     } else {
-                        return self
-                            .runtime_error(&format!("Invalid upvalue index {}", index), line);
+                        return self.runtime_error(
+                            ErrorKind::Error,
+                            &format!("Invalid upvalue index {}", index),
+                            line,
+                        );
                     }
                 }
                 OpCode::Upvalue(_, _) => {
                     return self.runtime_error(
+                        ErrorKind::Error,
                         "Unexpected upvalue instruction encountered outside of closure creation",
                         line,
                     );
@@ -374,29 +656,71 @@ impl VM {
                     let class = Class::new(name.clone());
                     self.push_stack(Value::class(class));
                 }
+                OpCode::Trait(name) => {
+                    let r#trait = Trait::new(name.clone());
+                    self.push_stack(Value::Trait(Rc::new(RefCell::new(r#trait))));
+                }
+                OpCode::Implement(count, own_members) => {
+                    self.implement_traits(*count, own_members, line)?;
+                }
                 OpCode::GetProperty(name) => {
                     let instance = self.peek_stack(line)?;
+                    self.check_private_access(&instance, name, line)?;
                     match instance {
                         Value::Instance(instance) => {
-                            if let Some(property) = instance.borrow().fields.get(name) {
+                            let getter =
+                                instance.borrow().class.borrow().getters.get(name).cloned();
+                            if let Some(getter) = getter {
+                                // The instance on top of the stack becomes `this`.
+                                let receiver_index = self.stack.len() - 1;
+                                self.call_closure(getter, 0, line, receiver_index)?;
+                            } else if let Some(property) = instance.borrow().fields.get(name) {
                                 self.pop_stack(line)?;
                                 self.push_stack(property.clone());
                             } else if let Some(method) =
                                 instance.borrow().class.borrow().methods.get(name)
+                            {
+                                let bound_method = BoundMethod::new(
+                                    method.clone(),
+                                    Value::Instance(instance.clone()),
+                                );
+                                self.pop_stack(line)?;
+                                self.push_stack(Value::bound_method(bound_method));
+                            } else {
+                                return self.runtime_error(
+                                    ErrorKind::PropertyError,
+                                    &format!("Undefined property '{}'", member_name(name)),
+                                    line,
+                                );
+                            }
+                        }
+                        Value::Class(class) => {
+                            if let Some(field) = Class::static_field(&class, name) {
+                                self.pop_stack(line)?;
+                                self.push_stack(field);
+                            } else if let Some(method) =
+                                class.borrow().static_methods.get(name).cloned()
                             {
                                 let bound_method =
-                                    BoundMethod::new(method.clone(), instance.clone());
+                                    BoundMethod::new(method, Value::Class(class.clone()));
                                 self.pop_stack(line)?;
                                 self.push_stack(Value::bound_method(bound_method));
                             } else {
                                 return self.runtime_error(
-                                    &format!("Undefined property '{}'", name),
+                                    ErrorKind::PropertyError,
+                                    &format!("Undefined property '{}'", member_name(name)),
                                     line,
                                 );
                             }
                         }
+                        Value::Enum(_) | Value::Variant(_) => {
+                            let property = self.enum_property(&instance, name, line)?;
+                            self.pop_stack(line)?;
+                            self.push_stack(property);
+                        }
                         _ => {
                             return self.runtime_error(
+                                ErrorKind::TypeError,
                                 &format!(
                                     "Only instances have properties. Expected instance, got {}",
                                     instance.type_name()
@@ -411,20 +735,57 @@ impl VM {
                     // We need to get both instance (depth 1) and value (depth 0)
                     let value = self.peek_stack_at(0, line)?;
                     let instance = self.peek_stack_at(1, line)?;
+                    self.check_private_access(&instance, name, line)?;
 
                     match instance {
                         Value::Instance(instance) => {
-                            instance
+                            let class = instance.borrow().class.clone();
+                            let setter = class.borrow().setters.get(name).cloned();
+                            if let Some(setter) = setter {
+                                // The setter returns the value, which replaces the instance.
+                                let receiver_index = self.stack.len() - 2;
+                                self.call_closure(setter, 1, line, receiver_index)?;
+                            } else if class.borrow().getters.contains_key(name) {
+                                return self.runtime_error(
+                                    ErrorKind::PropertyError,
+                                    &format!(
+                                        "Cannot assign to read-only property '{}'",
+                                        member_name(name)
+                                    ),
+                                    line,
+                                );
+                            } else {
+                                instance.borrow_mut().set_field(name.clone(), value.clone());
+                                // Pop both instance and value, then push value back (assignment returns the value)
+                                self.pop_stack(line)?;
+                                self.pop_stack(line)?;
+                                self.push_stack(value);
+                            }
+                        }
+                        Value::Class(class) => {
+                            // Class-level fields must be declared in the class
+                            // body, so a misspelt name is caught.
+                            let owner = match Class::static_field_owner(&class, name) {
+                                Some(owner) => owner,
+                                None => {
+                                    return self.runtime_error(
+                                        ErrorKind::PropertyError,
+                                        &format!("Undefined property '{}'", member_name(name)),
+                                        line,
+                                    );
+                                }
+                            };
+                            owner
                                 .borrow_mut()
-                                .fields
+                                .static_fields
                                 .insert(name.clone(), value.clone());
-                            // Pop both instance and value, then push value back (assignment returns the value)
                             self.pop_stack(line)?;
                             self.pop_stack(line)?;
                             self.push_stack(value);
                         }
                         _ => {
                             return self.runtime_error(
+                                ErrorKind::TypeError,
                                 &format!(
                                     "Only instances have properties. Expected instance, got {}",
                                     instance.type_name()
@@ -447,8 +808,17 @@ impl VM {
 
                             self.pop_stack(line)?;
                         }
+                        (Value::Trait(trait_rc), Value::Closure(closure_rc)) => {
+                            trait_rc
+                                .borrow_mut()
+                                .methods
+                                .insert(name.clone(), closure_rc.clone());
+
+                            self.pop_stack(line)?;
+                        }
                         _ => {
                             return self.runtime_error(
+                                ErrorKind::Error,
                                 &format!(
                                     "METHOD
                                       requires class and closure, got {} and {}",
@@ -460,51 +830,249 @@ impl VM {
                         }
                     }
                 }
-                OpCode::Invoke(name, arg_count) => {
-                    let arg_count = *arg_count;
-                    let receiver_index = self.stack.len() - arg_count - 1;
-                    let receiver = self.stack[receiver_index].clone();
+                OpCode::Getter(name) | OpCode::Setter(name) => {
+                    let method = self.peek_stack_at(0, line)?;
+                    let class_val = self.peek_stack_at(1, line)?;
 
-                    match receiver {
-                        Value::Instance(instance) => {
-                            if let Some(method) = instance.borrow().class.borrow().methods.get(name)
-                            {
-                                self.call_closure(method.clone(), arg_count, line, receiver_index)?;
-                            } else if let Some(field) = instance.borrow().fields.get(name) {
-                                let field_value = field.clone();
-                                self.stack[receiver_index] = field_value;
+                    match (&class_val, &method) {
+                        (Value::Class(class_rc), Value::Closure(closure_rc)) => {
+                            let mut class = class_rc.borrow_mut();
+                            let accessors = if matches!(instruction.op_code, OpCode::Getter(_)) {
+                                &mut class.getters
+                            } else {
+                                &mut class.setters
+                            };
+                            accessors.insert(name.clone(), closure_rc.clone());
+                            drop(class);
 
-                                match self.stack[receiver_index].clone() {
-                                    Value::Closure(closure) => {
-                                        self.call_closure(
-                                            closure,
-                                            arg_count,
-                                            line,
-                                            receiver_index,
-                                        )?;
-                                    }
-                                    _ => {
-                                        return self.runtime_error(
-                                            &format!(
-                                                "'{}' is not a method or callable field",
-                                                name
-                                            ),
-                                            line,
-                                        );
-                                    }
-                                }
+                            self.pop_stack(line)?;
+                        }
+                        (Value::Trait(trait_rc), Value::Closure(closure_rc)) => {
+                            let mut r#trait = trait_rc.borrow_mut();
+                            let accessors = if matches!(instruction.op_code, OpCode::Getter(_)) {
+                                &mut r#trait.getters
                             } else {
-                                return self.runtime_error(
-                                    &format!("Undefined property '{}'", name),
-                                    line,
-                                );
-                            }
+                                &mut r#trait.setters
+                            };
+                            accessors.insert(name.clone(), closure_rc.clone());
+                            drop(r#trait);
+
+                            self.pop_stack(line)?;
                         }
                         _ => {
                             return self.runtime_error(
+                                ErrorKind::Error,
                                 &format!(
-                                    "Only instances have methods. Expected instance, got {}",
-                                    receiver.type_name()
+                                    "Accessor '{}' requires class and closure, got {} and {}",
+                                    name,
+                                    class_val.type_name(),
+                                    method.type_name()
+                                ),
+                                line,
+                            );
+                        }
+                    }
+                }
+                OpCode::Field(name) => {
+                    let initializer = self.peek_stack_at(0, line)?;
+                    let class_val = self.peek_stack_at(1, line)?;
+
+                    match (&class_val, &initializer) {
+                        (Value::Class(class_rc), Value::Closure(closure_rc)) => {
+                            let mut class = class_rc.borrow_mut();
+                            let field = (name.clone(), closure_rc.clone());
+                            // Redeclaring an inherited field keeps its position.
+                            match class
+                                .field_initializers
+                                .iter()
+                                .position(|(existing, _)| existing == name)
+                            {
+                                Some(index) => class.field_initializers[index] = field,
+                                None => class.field_initializers.push(field),
+                            }
+                            drop(class);
+
+                            self.pop_stack(line)?;
+                        }
+                        _ => {
+                            return self.runtime_error(
+                                ErrorKind::Error,
+                                &format!(
+                                    "Field '{}' requires class and closure, got {} and {}",
+                                    name,
+                                    class_val.type_name(),
+                                    initializer.type_name()
+                                ),
+                                line,
+                            );
+                        }
+                    }
+                }
+                OpCode::CopyWith => {
+                    let changes = self.pop_stack(line)?;
+                    let receiver = self.pop_stack(line)?;
+                    let copy = self.copy_with(&receiver, &changes, line)?;
+                    self.push_stack(copy);
+                }
+                OpCode::AbstractMethod(name) => match self.peek_stack(line)? {
+                    Value::Class(class) => {
+                        let mut class = class.borrow_mut();
+                        class.methods.remove(name);
+                        class.abstract_methods.insert(name.clone());
+                    }
+                    Value::Trait(r#trait) => {
+                        let mut r#trait = r#trait.borrow_mut();
+                        r#trait.methods.remove(name);
+                        r#trait.abstract_methods.insert(name.clone());
+                    }
+                    other => {
+                        return self.runtime_error(
+                            ErrorKind::Error,
+                            &format!(
+                                "Abstract method '{}' requires class, got {}",
+                                name,
+                                other.type_name()
+                            ),
+                            line,
+                        );
+                    }
+                },
+                OpCode::StaticMethod(name) => {
+                    let method = self.peek_stack_at(0, line)?;
+                    let class_val = self.peek_stack_at(1, line)?;
+
+                    match (&class_val, &method) {
+                        (Value::Class(class_rc), Value::Closure(closure_rc)) => {
+                            class_rc
+                                .borrow_mut()
+                                .static_methods
+                                .insert(name.clone(), closure_rc.clone());
+
+                            self.pop_stack(line)?;
+                        }
+                        _ => {
+                            return self.runtime_error(
+                                ErrorKind::Error,
+                                &format!(
+                                    "STATIC_METHOD requires class and closure, got {} and {}",
+                                    class_val.type_name(),
+                                    method.type_name()
+                                ),
+                                line,
+                            );
+                        }
+                    }
+                }
+                OpCode::StaticField(name) => {
+                    let value = self.peek_stack_at(0, line)?;
+                    let class_val = self.peek_stack_at(1, line)?;
+
+                    match &class_val {
+                        Value::Class(class_rc) => {
+                            class_rc
+                                .borrow_mut()
+                                .static_fields
+                                .insert(name.clone(), value);
+
+                            self.pop_stack(line)?;
+                        }
+                        _ => {
+                            return self.runtime_error(
+                                ErrorKind::Error,
+                                &format!(
+                                    "STATIC_FIELD requires a class, got {}",
+                                    class_val.type_name()
+                                ),
+                                line,
+                            );
+                        }
+                    }
+                }
+                OpCode::Invoke(name, arg_count) => {
+                    let arg_count = *arg_count;
+                    let receiver_index = self.stack.len() - arg_count - 1;
+                    let receiver = self.stack[receiver_index].clone();
+                    self.check_private_access(&receiver, name, line)?;
+
+                    match receiver {
+                        Value::Instance(instance) => {
+                            let getter =
+                                instance.borrow().class.borrow().getters.get(name).cloned();
+                            if let Some(getter) = getter {
+                                let bound_getter =
+                                    BoundMethod::new(getter, Value::Instance(instance.clone()));
+                                self.stack[receiver_index] = self.call_reentrant(
+                                    Value::bound_method(bound_getter),
+                                    vec![],
+                                    line,
+                                )?;
+                                self.call(receiver_index, arg_count, line)?;
+                            } else if let Some(method) =
+                                instance.borrow().class.borrow().methods.get(name)
+                            {
+                                self.call_closure(method.clone(), arg_count, line, receiver_index)?;
+                            } else if let Some(field) = instance.borrow().fields.get(name) {
+                                let field_value = field.clone();
+                                self.stack[receiver_index] = field_value;
+
+                                match self.stack[receiver_index].clone() {
+                                    Value::Closure(closure) => {
+                                        self.call_closure(
+                                            closure,
+                                            arg_count,
+                                            line,
+                                            receiver_index,
+                                        )?;
+                                    }
+                                    _ => {
+                                        return self.runtime_error(
+                                            ErrorKind::TypeError,
+                                            &format!(
+                                                "'{}' is not a method or callable field",
+                                                name
+                                            ),
+                                            line,
+                                        );
+                                    }
+                                }
+                            } else {
+                                return self.runtime_error(
+                                    ErrorKind::PropertyError,
+                                    &format!("Undefined property '{}'", member_name(name)),
+                                    line,
+                                );
+                            }
+                        }
+                        Value::Class(class) => {
+                            if let Some(field) = Class::static_field(&class, name) {
+                                self.stack[receiver_index] = field;
+                                self.call(receiver_index, arg_count, line)?;
+                            } else if let Some(method) =
+                                class.borrow().static_methods.get(name).cloned()
+                            {
+                                self.call_closure(method, arg_count, line, receiver_index)?;
+                            } else {
+                                return self.runtime_error(
+                                    ErrorKind::PropertyError,
+                                    &format!("Undefined property '{}'", member_name(name)),
+                                    line,
+                                );
+                            }
+                        }
+                        Value::Fiber(fiber) => {
+                            self.invoke_fiber_method(fiber, name, arg_count, receiver_index, line)?;
+                        }
+                        Value::Enum(_) | Value::Variant(_) => {
+                            self.stack[receiver_index] =
+                                self.enum_property(&receiver, name, line)?;
+                            self.call(receiver_index, arg_count, line)?;
+                        }
+                        _ => {
+                            return self.runtime_error(
+                                ErrorKind::TypeError,
+                                &format!(
+                                    "Only instances have methods. Expected instance, got {}",
+                                    receiver.type_name()
                                 ),
                                 line,
                             );
@@ -517,15 +1085,34 @@ impl VM {
 
                     match (&superclass, &subclass) {
                         (Value::Class(superclass_rc), Value::Class(subclass_rc)) => {
-                            subclass_rc
-                                .borrow_mut()
-                                .methods
-                                .extend(superclass_rc.borrow().methods.clone());
+                            {
+                                let mut subclass = subclass_rc.borrow_mut();
+                                subclass
+                                    .methods
+                                    .extend(superclass_rc.borrow().methods.clone());
+                                subclass
+                                    .getters
+                                    .extend(superclass_rc.borrow().getters.clone());
+                                subclass
+                                    .setters
+                                    .extend(superclass_rc.borrow().setters.clone());
+                                subclass
+                                    .static_methods
+                                    .extend(superclass_rc.borrow().static_methods.clone());
+                                subclass
+                                    .abstract_methods
+                                    .extend(superclass_rc.borrow().abstract_methods.clone());
+                                subclass.field_initializers.extend(
+                                    superclass_rc.borrow().field_initializers.iter().cloned(),
+                                );
+                                subclass.superclass = Some(Rc::clone(superclass_rc));
+                            }
 
                             self.pop_stack(line)?;
                         }
                         _ => {
                             return self.runtime_error(
+ ErrorKind::TypeError,
                                 &format!(
                                     "Cannot inherit from non-class value. Expected class inheriting from class, got {} inheriting from {}",
                                     subclass.type_name(),
@@ -538,21 +1125,29 @@ impl VM {
                 }
                 OpCode::GetSuper(method_name) => {
                     let superclass = self.pop_stack(line)?;
-                    let instance = self.peek_stack(line)?;
+                    let instance = self.pop_stack(line)?;
 
                     match (&superclass, &instance) {
                         (Value::Class(superclass_rc), Value::Instance(instance_rc)) => {
                             let superclass = superclass_rc.borrow();
 
                             let superclass_name = superclass.name.clone();
-                            let method_option = superclass.methods.get(method_name).clone();
-
-                            if let Some(method_rc) = method_option {
-                                let bound_method =
-                                    BoundMethod::new(method_rc.clone(), instance_rc.clone());
+                            let method_option = superclass.methods.get(method_name);
+
+                            if let Some(getter) = superclass.getters.get(method_name).cloned() {
+                                drop(superclass);
+                                self.push_stack(instance.clone());
+                                let receiver_index = self.stack.len() - 1;
+                                self.call_closure(getter, 0, line, receiver_index)?;
+                            } else if let Some(method_rc) = method_option {
+                                let bound_method = BoundMethod::new(
+                                    method_rc.clone(),
+                                    Value::Instance(instance_rc.clone()),
+                                );
                                 self.push_stack(Value::bound_method(bound_method));
                             } else {
                                 return self.runtime_error(
+                                    ErrorKind::PropertyError,
                                     &format!(
                                         "Cannot find method {} in superclass {}",
                                         method_name, superclass_name
@@ -560,17 +1155,59 @@ impl VM {
                                     line,
                                 );
                             }
-
-                            ()
+                        }
+                        (Value::Class(superclass_rc), Value::Class(_)) => {
+                            let method = superclass_rc
+                                .borrow()
+                                .static_methods
+                                .get(method_name)
+                                .cloned();
+
+                            if let Some(method_rc) = method {
+                                let bound_method = BoundMethod::new(method_rc, instance.clone());
+                                self.push_stack(Value::bound_method(bound_method));
+                            } else {
+                                return self.runtime_error(
+                                    ErrorKind::PropertyError,
+                                    &format!(
+                                        "Cannot find method {} in superclass {}",
+                                        method_name,
+                                        superclass_rc.borrow().name
+                                    ),
+                                    line,
+                                );
+                            }
                         }
                         (Value::Class(_), _) => {
                             return self.runtime_error(
+ ErrorKind::TypeError,
                                 &format!("Cannot run method {} on non-instance value. Expected instance, got {}", method_name, instance.type_name()),
                                 line,
                             );
                         }
                         (_, _) => {
                             return self.runtime_error(
+                                ErrorKind::TypeError,
+                                &format!(
+                                    "Cannot resolve 'super'. Expected class, got {}",
+                                    superclass.type_name()
+                                ),
+                                line,
+                            );
+                        }
+                    }
+                }
+                OpCode::SetSuper(name) => {
+                    // Stack layout: [..., instance, value, superclass]
+                    let superclass = self.pop_stack(line)?;
+
+                    let setter = match &superclass {
+                        Value::Class(superclass_rc) => {
+                            superclass_rc.borrow().setters.get(name).cloned()
+                        }
+                        _ => {
+                            return self.runtime_error(
+                                ErrorKind::TypeError,
                                 &format!(
                                     "Cannot resolve 'super'. Expected class, got {}",
                                     superclass.type_name()
@@ -578,6 +1215,23 @@ impl VM {
                                 line,
                             );
                         }
+                    };
+
+                    match setter {
+                        Some(setter) => {
+                            let receiver_index = self.stack.len() - 2;
+                            self.call_closure(setter, 1, line, receiver_index)?;
+                        }
+                        None => {
+                            return self.runtime_error(
+                                ErrorKind::PropertyError,
+                                &format!(
+                                    "Cannot find setter {} in superclass {}",
+                                    name, superclass
+                                ),
+                                line,
+                            );
+                        }
                     }
                 }
                 OpCode::SuperInvoke(method_name, arg_count) => {
@@ -601,6 +1255,7 @@ impl VM {
                                 )?;
                             } else {
                                 return self.runtime_error(
+                                    ErrorKind::PropertyError,
                                     &format!(
                                         "Cannot find method {} in superclass {}",
                                         method_name, superclass_name
@@ -609,8 +1264,30 @@ impl VM {
                                 );
                             }
                         }
+                        (Value::Class(superclass_rc), Value::Class(_)) => {
+                            let method = superclass_rc
+                                .borrow()
+                                .static_methods
+                                .get(method_name)
+                                .cloned();
+
+                            if let Some(method_rc) = method {
+                                self.call_closure(method_rc, arg_count, line, instance_index)?;
+                            } else {
+                                return self.runtime_error(
+                                    ErrorKind::PropertyError,
+                                    &format!(
+                                        "Cannot find method {} in superclass {}",
+                                        method_name,
+                                        superclass_rc.borrow().name
+                                    ),
+                                    line,
+                                );
+                            }
+                        }
                         (Value::Class(_), _) => {
                             return self.runtime_error(
+ ErrorKind::TypeError,
                                 &format!(
                                     "Cannot invoke method {} on non-instance value. Expected instance, got {}",
                                     method_name,
@@ -621,6 +1298,7 @@ impl VM {
                         }
                         (_, _) => {
                             return self.runtime_error(
+                                ErrorKind::TypeError,
                                 &format!(
                                     "Cannot resolve 'super'. Expected class, got {}",
                                     superclass.type_name()
@@ -630,56 +1308,1316 @@ impl VM {
                         }
                     }
                 }
-            }
-
-            if self.debug {
-                eprintln!(
-                    "Handled instruction: {:?}\nLine: {}\nStack: {}\n",
-                    &instruction.op_code,
-                    line,
-                    format_stack(&self.stack)
-                );
-            }
-        }
-    }
-
-    fn call_closure(
-        &mut self,
-        closure: Rc<Closure>,
-        arg_count: usize,
-        line: usize,
-        callee_index: usize,
-    ) -> Result<(), InterpretError> {
-        let arity = closure.function.arity;
-
-        if arity != arg_count {
-            return self.runtime_error(
-                &format!("Expected {} arguments but got {}", arity, arg_count),
-                line,
-            );
-        }
-
-        self.call_frame_stack.push(CallFrame {
-            closure,
-            ip: 0,
-            slot_start: callee_index,
-        });
-
-        Ok(())
-    }
+                OpCode::Yield => {
+                    let value = self.pop_stack(line)?;
+                    self.suspend_fiber(value, FiberState::Suspended, line)?;
+                }
+                OpCode::BuildList(count) => {
+                    let elements = self.stack.split_off(self.stack.len() - count);
+                    self.push_stack(Value::list(elements));
+                }
+                OpCode::BuildMap(count) => {
+                    let entries = self.stack.split_off(self.stack.len() - count * 2);
+                    let mut map = Map::new();
+                    for pair in entries.chunks(2) {
+                        let key = self.map_key(&pair[0], line)?;
+                        map.insert(key, pair[1].clone());
+                    }
+                    self.push_stack(Value::map(map));
+                }
+                OpCode::BuildTuple(count) => {
+                    let elements = self.stack.split_off(self.stack.len() - count);
+                    self.push_stack(Value::tuple(elements));
+                }
+                OpCode::Unpack(count, rest) => {
+                    let value = self.pop_stack(line)?;
+                    let mut elements = match &value {
+                        Value::Tuple(values) => values.to_vec(),
+                        Value::List(list) => list.borrow().clone(),
+                        _ => {
+                            return self.runtime_error(
+                                ErrorKind::TypeError,
+                                &format!("Cannot unpack {}", value.type_name()),
+                                line,
+                            );
+                        }
+                    };
 
-    fn next_instruction(&mut self) -> Option<&Instruction> {
-        self.call_frame_stack.last_mut()?.next()
-    }
+                    if elements.len() < *count || (!rest && elements.len() > *count) {
+                        let expected = if *rest {
+                            format!("at least {}", count)
+                        } else {
+                            count.to_string()
+                        };
+                        return self.runtime_error(
+                            ErrorKind::ArityError,
+                            &format!(
+                                "Expected {} values to unpack but got {}",
+                                expected,
+                                elements.len()
+                            ),
+                            line,
+                        );
+                    }
 
-    fn peek_instruction(&self) -> Option<&Instruction> {
-        self.call_frame_stack.last()?.peek()
-    }
+                    let remaining = elements.split_off(*count);
+                    self.stack.extend(elements);
+                    if *rest {
+                        self.push_stack(Value::list(remaining));
+                    }
+                }
+                OpCode::ExtendList => {
+                    let elements = match self.pop_stack(line)? {
+                        Value::List(list) => list.borrow().clone(),
+                        Value::Tuple(values) => values.to_vec(),
+                        value => {
+                            return self.runtime_error(
+                                ErrorKind::TypeError,
+                                &format!(
+                                    "Can only spread a list or tuple, got {}",
+                                    value.type_name()
+                                ),
+                                line,
+                            );
+                        }
+                    };
 
-    fn offset(&mut self, offset: usize) {
-        if let Some(frame) = self.call_frame_stack.last_mut() {
-            frame.offset(offset);
-        }
+                    match self.peek_stack(line)? {
+                        Value::List(list) => list.borrow_mut().extend(elements),
+                        value => {
+                            return self.runtime_error(
+                                ErrorKind::TypeError,
+                                &format!("Cannot extend {}", value.type_name()),
+                                line,
+                            );
+                        }
+                    }
+                }
+                OpCode::BuildRange(inclusive) => {
+                    let step = self.pop_stack(line)?;
+                    let end = self.pop_stack(line)?;
+                    let start = self.pop_stack(line)?;
+                    let range = self.build_range(&start, &end, &step, *inclusive, line)?;
+                    self.push_stack(Value::Range(Rc::new(range)));
+                }
+                OpCode::GetIndex => {
+                    let index = self.pop_stack(line)?;
+                    let target = self.pop_stack(line)?;
+
+                    match (&target, &index) {
+                        (Value::List(list), Value::Range(range)) => {
+                            let length = list.borrow().len();
+                            let positions = self.slice_positions(range, length, line)?;
+                            let list = list.borrow();
+                            let slice = positions.into_iter().map(|i| list[i].clone()).collect();
+                            drop(list);
+                            self.push_stack(Value::list(slice));
+                        }
+                        (Value::Tuple(values), Value::Range(range)) => {
+                            let positions = self.slice_positions(range, values.len(), line)?;
+                            let slice = positions.into_iter().map(|i| values[i].clone()).collect();
+                            self.push_stack(Value::Tuple(Rc::new(slice)));
+                        }
+                        (Value::String(string), Value::Range(range)) => {
+                            // Positions count characters, not bytes.
+                            let chars = string.chars().collect::<Vec<_>>();
+                            let positions = self.slice_positions(range, chars.len(), line)?;
+                            let slice = positions.into_iter().map(|i| chars[i]).collect();
+                            self.push_stack(Value::String(Rc::new(slice)));
+                        }
+                        (Value::List(list), Value::Number(number)) => {
+                            let position =
+                                self.list_position(*number, list.borrow().len(), line)?;
+                            let element = list.borrow()[position].clone();
+                            self.push_stack(element);
+                        }
+                        (Value::Tuple(values), Value::Number(number)) => {
+                            let position = self.list_position(*number, values.len(), line)?;
+                            self.push_stack(values[position].clone());
+                        }
+                        (Value::String(string), Value::Number(number)) => {
+                            let length = string.chars().count();
+                            let position = self.list_position(*number, length, line)?;
+                            let c = string.chars().nth(position).unwrap_or_default();
+                            self.push_stack(Value::String(Rc::new(c.to_string())));
+                        }
+                        (Value::List(_) | Value::Tuple(_) | Value::String(_), _) => {
+                            return self.runtime_error(
+                                ErrorKind::TypeError,
+                                &format!(
+                                    "Index must be a number or range, got {}",
+                                    index.type_name()
+                                ),
+                                line,
+                            );
+                        }
+                        (Value::Map(map), _) => {
+                            let key = self.map_key(&index, line)?;
+                            let value = map.borrow().get(&key).unwrap_or(Value::Nil);
+                            self.push_stack(value);
+                        }
+                        (Value::Enum(enumeration), Value::String(name)) => {
+                            let variant = enumeration.variant(name).unwrap_or(Value::Nil);
+                            self.push_stack(variant);
+                        }
+                        (Value::Enum(_), _) => {
+                            return self.runtime_error(
+                                ErrorKind::TypeError,
+                                &format!(
+                                    "Enums are indexed by variant name, got {}",
+                                    index.type_name()
+                                ),
+                                line,
+                            );
+                        }
+                        _ if self.call_operator(
+                            "__index__",
+                            &[target.clone(), index.clone()],
+                            line,
+                        )? => {}
+                        _ => {
+                            return self.runtime_error(
+                                ErrorKind::TypeError,
+                                &format!(
+                                    "Only lists, maps, tuples, strings and enums can be indexed, got {}",
+                                    target.type_name()
+                                ),
+                                line,
+                            );
+                        }
+                    }
+                }
+                OpCode::SetIndex => {
+                    let value = self.pop_stack(line)?;
+                    let index = self.pop_stack(line)?;
+                    let target = self.pop_stack(line)?;
+
+                    match (&target, &index) {
+                        (Value::List(list), Value::Number(number)) => {
+                            let position =
+                                self.list_position(*number, list.borrow().len(), line)?;
+                            list.borrow_mut()[position] = value.clone();
+                            self.push_stack(value);
+                        }
+                        (Value::List(_), _) => {
+                            return self.runtime_error(
+                                ErrorKind::TypeError,
+                                &format!("Index must be a number, got {}", index.type_name()),
+                                line,
+                            );
+                        }
+                        (Value::Map(map), _) => {
+                            let key = self.map_key(&index, line)?;
+                            map.borrow_mut().insert(key, value.clone());
+                            self.push_stack(value);
+                        }
+                        _ => {
+                            return self.runtime_error(
+                                ErrorKind::TypeError,
+                                &format!(
+                                    "Only list and map elements can be assigned, got {}",
+                                    target.type_name()
+                                ),
+                                line,
+                            );
+                        }
+                    }
+                }
+                OpCode::GetIterator => {
+                    let iterable = self.pop_stack(line)?;
+                    let iterator = self.make_iterator(iterable, line)?;
+                    self.push_stack(iterator);
+                }
+                OpCode::IteratorNext(offset) => {
+                    let iterator = self.pop_stack(line)?;
+
+                    let next = match &iterator {
+                        Value::Iterator(iterator) => iterator.borrow_mut().advance(),
+                        Value::Instance(instance) => {
+                            // User iterators are asked hasNext() before each
+                            // next(), so nil is an ordinary element.
+                            let has_next = self.call_iterator_method(instance, "hasNext", line)?;
+                            if has_next.is_falsey() {
+                                None
+                            } else {
+                                Some(self.call_iterator_method(instance, "next", line)?)
+                            }
+                        }
+                        _ => {
+                            return self.runtime_error(
+                                ErrorKind::TypeError,
+                                &format!("Cannot iterate with {}", iterator.type_name()),
+                                line,
+                            );
+                        }
+                    };
+
+                    match next {
+                        Some(value) => self.push_stack(value),
+                        None => self.offset(*offset),
+                    }
+                }
+                OpCode::Switch(table) => {
+                    let value = self.pop_stack(line)?;
+                    // An overloaded `==` continues at the `Equal` tests that follow.
+                    let overloads_equal = matches!(&value, Value::Instance(instance)
+                        if instance.borrow().class.borrow().methods.contains_key("__eq__"));
+                    if !overloads_equal {
+                        let offset = SwitchKey::from_value(&value)
+                            .and_then(|key| table.cases.get(&key).copied())
+                            .unwrap_or(table.default);
+
+                        self.offset(offset);
+                    }
+                }
+                OpCode::InRange(inclusive) => {
+                    let high = self.pop_stack(line)?;
+                    let low = self.pop_stack(line)?;
+                    let value = self.pop_stack(line)?;
+
+                    let in_range = match (value, low, high) {
+                        (Value::Number(value), Value::Number(low), Value::Number(high)) => {
+                            low <= value && (value < high || *inclusive && value == high)
+                        }
+                        _ => false,
+                    };
+                    self.push_stack(Value::Bool(in_range));
+                }
+                OpCode::Is => {
+                    let class = self.pop_stack(line)?;
+                    let value = self.pop_stack(line)?;
+
+                    let is_instance = match (&value, &class) {
+                        (Value::Instance(instance), Value::Class(class)) => {
+                            instance.borrow().class.borrow().is_subclass_of(class)
+                        }
+                        (Value::Variant(variant), Value::Enum(enumeration)) => {
+                            Rc::ptr_eq(&variant.definition, &enumeration.definition)
+                        }
+                        (Value::Variant(variant), Value::Variant(expected)) => {
+                            variant.same_variant(expected)
+                        }
+                        (Value::Instance(instance), Value::Trait(r#trait)) => {
+                            instance.borrow().class.borrow().implements(r#trait)
+                        }
+                        (
+                            _,
+                            Value::Class(_) | Value::Trait(_) | Value::Enum(_) | Value::Variant(_),
+                        ) => false,
+                        _ => {
+                            return self.runtime_error(
+                                ErrorKind::TypeError,
+                                &format!(
+                                    "Right operand of 'is' must be a class, trait, enum or variant, got {}",
+                                    class.type_name()
+                                ),
+                                line,
+                            );
+                        }
+                    };
+                    self.push_stack(Value::Bool(is_instance));
+                }
+                OpCode::NoMatch => {
+                    let value = self.pop_stack(line)?;
+                    return self.runtime_error(
+                        ErrorKind::MatchError,
+                        &format!("No match arm for value {}", value),
+                        line,
+                    );
+                }
+            }
+
+            if self.debug {
+                eprintln!(
+                    "Handled instruction: {:?}\nLine: {}\nStack: {}\n",
+                    &instruction.op_code,
+                    line,
+                    format_stack(&self.stack)
+                );
+            }
+        }
+    }
+
+    /// Converts `value` to a string for interpolation, concatenation and
+    /// `str()`: strings as they are, anything else as `print` shows it.
+    fn stringify(&mut self, value: Value, line: usize) -> Result<Rc<String>, InterpretError> {
+        match value {
+            Value::String(string) => Ok(string),
+            value => Ok(Rc::new(self.display(&value, line)?)),
+        }
+    }
+
+    /// Like `stringify`, for native functions. A runtime error raised by a
+    /// `toString()` call is kept and re-raised when the native returns, so
+    /// its kind and stack trace are not lost.
+    pub fn native_stringify(&mut self, value: Value) -> Result<Rc<String>, NativeError> {
+        let line = self.current_line();
+        self.stringify(value, line).map_err(|error| {
+            let message = error.to_string();
+            self.native_error = Some(error);
+            (ErrorKind::Error, message)
+        })
+    }
+
+    /// The text `print` shows for `value`.
+    ///
+    /// Instances whose class defines `toString()` show its result, which must
+    /// be a string, and lists, maps and tuples show their elements the same
+    /// way. A list or map met again while it is being shown appears as
+    /// `[...]`, and an instance converted from inside its own `toString()`
+    /// falls back to its default form.
+    fn display(&mut self, value: &Value, line: usize) -> Result<String, InterpretError> {
+        match value {
+            Value::Closure(closure) => Ok(closure.function.to_string()),
+            Value::Instance(instance) => {
+                let key = Rc::as_ptr(instance) as *const ();
+                let method = instance
+                    .borrow()
+                    .class
+                    .borrow()
+                    .methods
+                    .get("toString")
+                    .cloned();
+
+                match method {
+                    Some(method) if !self.converting.contains(&key) => {
+                        let bound_method = BoundMethod::new(method, value.clone());
+                        self.converting.push(key);
+                        let result =
+                            self.call_reentrant(Value::bound_method(bound_method), vec![], line);
+                        self.converting.pop();
+
+                        match result? {
+                            Value::String(string) => Ok(string.to_string()),
+                            result => {
+                                self.runtime_error(
+                                    ErrorKind::TypeError,
+                                    &format!(
+                                        "toString() must return a string, got {}",
+                                        result.type_name()
+                                    ),
+                                    line,
+                                )?;
+                                unreachable!()
+                            }
+                        }
+                    }
+                    _ => Ok(value.to_string()),
+                }
+            }
+            Value::List(list) => {
+                let key = Rc::as_ptr(list) as *const ();
+                if self.converting.contains(&key) {
+                    return Ok("[...]".to_string());
+                }
+
+                let elements = list.borrow().clone();
+                self.converting.push(key);
+                let result = self.display_all(&elements, line);
+                self.converting.pop();
+
+                Ok(format!("[{}]", result?))
+            }
+            Value::Map(map) => {
+                let key = Rc::as_ptr(map) as *const ();
+                if self.converting.contains(&key) {
+                    return Ok("[...]".to_string());
+                }
+                if map.borrow().is_empty() {
+                    return Ok("[:]".to_string());
+                }
+
+                let entries = map
+                    .borrow()
+                    .entries()
+                    .map(|(key, value)| (key, value.clone()))
+                    .collect::<Vec<_>>();
+                self.converting.push(key);
+                let result = entries
+                    .iter()
+                    .map(|(key, value)| Ok(format!("{}: {}", key, self.display(value, line)?)))
+                    .collect::<Result<Vec<_>, InterpretError>>();
+                self.converting.pop();
+
+                Ok(format!("[{}]", result?.join(", ")))
+            }
+            Value::Tuple(values) => {
+                let elements = self.display_all(values, line)?;
+                match values.len() {
+                    1 => Ok(format!("({},)", elements)),
+                    _ => Ok(format!("({})", elements)),
+                }
+            }
+            Value::Variant(variant) => match &variant.values {
+                Some(values) if !values.is_empty() => Ok(format!(
+                    "{}.{}({})",
+                    variant.definition.name,
+                    variant.name(),
+                    self.display_all(values, line)?
+                )),
+                _ => Ok(value.to_string()),
+            },
+            _ => Ok(value.to_string()),
+        }
+    }
+
+    fn display_all(&mut self, values: &[Value], line: usize) -> Result<String, InterpretError> {
+        let mut elements = Vec::with_capacity(values.len());
+        for value in values {
+            elements.push(self.display(value, line)?);
+        }
+        Ok(elements.join(", "))
+    }
+
+    /// Concatenates a string with an instance whose class defines
+    /// `toString()`, on either side of `+`.
+    fn concatenate_converted(
+        &mut self,
+        a: &Value,
+        b: &Value,
+        line: usize,
+    ) -> Result<Option<Value>, InterpretError> {
+        let converts = |value: &Value| match value {
+            Value::Instance(instance) => instance
+                .borrow()
+                .class
+                .borrow()
+                .methods
+                .contains_key("toString"),
+            _ => false,
+        };
+
+        match (a, b) {
+            (Value::String(_), other) | (other, Value::String(_)) if converts(other) => {
+                let a = self.stringify(a.clone(), line)?;
+                let b = self.stringify(b.clone(), line)?;
+                Ok(Some(Value::String(Rc::new(format!("{}{}", a, b)))))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Calls the value at `callee_index` with the `arg_count` values above it.
+    /// Closures get a new frame; natives run immediately and leave their result.
+    fn call(&mut self, callee_index: usize, arg_count: usize, line: usize) -> InterpretResult {
+        match self.stack[callee_index].clone() {
+            Value::Closure(closure) => self.call_closure(closure, arg_count, line, callee_index),
+
+            Value::NativeFunction(native) => {
+                if !native.arity.accepts(arg_count) {
+                    return self.runtime_error(
+                        ErrorKind::ArityError,
+                        &format!("Expected {} arguments but got {}", native.arity, arg_count),
+                        line,
+                    );
+                }
+
+                let args = self.stack.split_off(callee_index + 1);
+                self.stack.truncate(callee_index);
+
+                match (native.function)(self, args) {
+                    Ok(result) => {
+                        self.push_stack(result);
+                        Ok(())
+                    }
+                    Err((kind, message)) => match self.native_error.take() {
+                        Some(error) => Err(error),
+                        None => self.runtime_error(kind, &message, line),
+                    },
+                }
+            }
+
+            Value::Class(class) => {
+                let missing = class.borrow().missing_methods().join(", ");
+                if !missing.is_empty() {
+                    return self.runtime_error(
+                        ErrorKind::TypeError,
+                        &format!(
+                            "Cannot instantiate abstract class {} without implementations of {}",
+                            class.borrow().name,
+                            missing
+                        ),
+                        line,
+                    );
+                }
+
+                let mut instance = Instance::new(class.clone());
+                if self.is_error_class(&class.borrow()) {
+                    Self::set_error_location(&mut instance, line, &self.stack_trace());
+                }
+                let instance = Value::instance(instance);
+                self.stack[callee_index] = instance.clone();
+
+                let fields = class.borrow().field_initializers.clone();
+                for (name, initializer) in fields {
+                    let method =
+                        Value::bound_method(BoundMethod::new(initializer, instance.clone()));
+                    let value = self.call_reentrant(method, Vec::new(), line)?;
+                    if let Value::Instance(instance) = &instance {
+                        instance.borrow_mut().set_field(name, value);
+                    }
+                }
+
+                if let Some(initializer) = class.borrow().methods.get("init") {
+                    self.call_closure(initializer.clone(), arg_count, line, callee_index)
+                } else if arg_count != 0 {
+                    self.runtime_error(
+                        ErrorKind::ArityError,
+                        &format!("Expected 0 arguments but got {}", arg_count),
+                        line,
+                    )
+                } else {
+                    Ok(())
+                }
+            }
+
+            Value::BoundMethod(bound_method) => {
+                let closure = Rc::clone(&bound_method.borrow().method);
+                self.stack[callee_index] = bound_method.borrow().receiver.clone();
+                self.call_closure(closure, arg_count, line, callee_index)
+            }
+
+            Value::Instance(instance) => {
+                let method = instance
+                    .borrow()
+                    .class
+                    .borrow()
+                    .methods
+                    .get("__call__")
+                    .cloned();
+                match method {
+                    Some(method) => self.call_closure(method, arg_count, line, callee_index),
+                    None => self.runtime_error(
+                        ErrorKind::TypeError,
+                        "Cannot call non-function value",
+                        line,
+                    ),
+                }
+            }
+
+            Value::Variant(variant) if variant.is_constructor() => {
+                let arity = variant.fields().len();
+                if arg_count != arity {
+                    return self.runtime_error(
+                        ErrorKind::ArityError,
+                        &format!("Expected {} arguments but got {}", arity, arg_count),
+                        line,
+                    );
+                }
+
+                let values = self.stack.split_off(callee_index + 1);
+                self.stack[callee_index] = Value::Variant(Rc::new(Variant::new(
+                    variant.definition.clone(),
+                    variant.index,
+                    Some(values),
+                )));
+                Ok(())
+            }
+
+            _ => self.runtime_error(ErrorKind::TypeError, "Cannot call non-function value", line),
+        }
+    }
+
+    /// The copy a record's `copyWith` returns: `receiver` with the fields
+    /// named in `changes`, a list of name and value pairs, replaced.
+    fn copy_with(
+        &mut self,
+        receiver: &Value,
+        changes: &Value,
+        line: usize,
+    ) -> Result<Value, InterpretError> {
+        let (instance, changes) = match (receiver, changes) {
+            (Value::Instance(instance), Value::List(changes)) => {
+                (instance, changes.borrow().clone())
+            }
+            _ => {
+                self.runtime_error(
+                    ErrorKind::Error,
+                    &format!(
+                        "COPY_WITH requires instance and list, got {} and {}",
+                        receiver.type_name(),
+                        changes.type_name()
+                    ),
+                    line,
+                )?;
+                unreachable!()
+            }
+        };
+
+        if changes.len() % 2 != 0 {
+            self.runtime_error(
+                ErrorKind::ArityError,
+                &format!(
+                    "copyWith expects field name and value pairs, got {} arguments",
+                    changes.len()
+                ),
+                line,
+            )?;
+        }
+
+        let mut copy = instance.borrow().clone();
+        for pair in changes.chunks(2) {
+            match &pair[0] {
+                Value::String(name) if copy.fields.contains_key(name.as_str()) => {
+                    copy.set_field(name.to_string(), pair[1].clone());
+                }
+                Value::String(name) => {
+                    self.runtime_error(
+                        ErrorKind::PropertyError,
+                        &format!("Undefined field '{}'", name),
+                        line,
+                    )?;
+                }
+                other => {
+                    self.runtime_error(
+                        ErrorKind::TypeError,
+                        &format!("copyWith expects a field name, got {}", other.type_name()),
+                        line,
+                    )?;
+                }
+            }
+        }
+
+        Ok(Value::instance(copy))
+    }
+
+    /// Private members can only be reached on the class that declares them,
+    /// its subclasses and their instances.
+    fn check_private_access(&mut self, target: &Value, key: &str, line: usize) -> InterpretResult {
+        let (owner, name) = match split_private_key(key) {
+            Some(parts) => parts,
+            None => return Ok(()),
+        };
+        let class = match target {
+            Value::Instance(instance) => instance.borrow().class.clone(),
+            Value::Class(class) => class.clone(),
+            _ => return Ok(()),
+        };
+
+        if class.borrow().declares_privates_of(owner) {
+            return Ok(());
+        }
+
+        self.runtime_error(
+            ErrorKind::PropertyError,
+            &format!(
+                "Cannot access private member '{}' of {} on {}",
+                name, owner, target
+            ),
+            line,
+        )
+    }
+
+    /// Pops `count` traits and copies their members into the class beneath
+    /// them. Two traits providing the same member is an error unless the
+    /// class declares that member itself.
+    fn implement_traits(
+        &mut self,
+        count: usize,
+        own_members: &[String],
+        line: usize,
+    ) -> InterpretResult {
+        let mut traits = Vec::with_capacity(count);
+        for _ in 0..count {
+            match self.pop_stack(line)? {
+                Value::Trait(r#trait) => traits.push(r#trait),
+                other => {
+                    return self.runtime_error(
+                        ErrorKind::TypeError,
+                        &format!(
+                            "Can only use traits after 'with', got {}",
+                            other.type_name()
+                        ),
+                        line,
+                    );
+                }
+            }
+        }
+        traits.reverse();
+
+        let class_rc = match self.peek_stack(line)? {
+            Value::Class(class) => class,
+            other => {
+                return self.runtime_error(
+                    ErrorKind::TypeError,
+                    &format!("Only classes can use traits, got {}", other.type_name()),
+                    line,
+                );
+            }
+        };
+
+        let mut providers: HashMap<String, String> = HashMap::new();
+        let mut conflict = None;
+        for r#trait in &traits {
+            let r#trait = r#trait.borrow();
+            for member in r#trait.member_names() {
+                if own_members.contains(member) {
+                    continue;
+                }
+                match providers.get(member) {
+                    Some(provider) if *provider != r#trait.name => {
+                        conflict = Some(format!(
+                            "Member '{}' is provided by both traits {} and {}",
+                            member, provider, r#trait.name
+                        ));
+                        break;
+                    }
+                    _ => {
+                        providers.insert(member.clone(), r#trait.name.clone());
+                    }
+                }
+            }
+        }
+        if let Some(message) = conflict {
+            return self.runtime_error(ErrorKind::TypeError, &message, line);
+        }
+
+        let mut class = class_rc.borrow_mut();
+        for r#trait in traits {
+            {
+                let r#trait = r#trait.borrow();
+                class.methods.extend(r#trait.methods.clone());
+                class.getters.extend(r#trait.getters.clone());
+                class.setters.extend(r#trait.setters.clone());
+                class
+                    .abstract_methods
+                    .extend(r#trait.abstract_methods.clone());
+            }
+            class.traits.push(r#trait);
+        }
+
+        Ok(())
+    }
+
+    /// Reads `name` from an enum, which holds its variants, or from a
+    /// variant, which holds its associated values and its `name`.
+    fn enum_property(
+        &mut self,
+        target: &Value,
+        name: &str,
+        line: usize,
+    ) -> Result<Value, InterpretError> {
+        let property = match target {
+            Value::Enum(enumeration) => enumeration.variant(name),
+            Value::Variant(variant) => variant.get(name).or_else(|| {
+                (name == "name").then(|| Value::String(Rc::new(variant.name().to_string())))
+            }),
+            _ => None,
+        };
+
+        match property {
+            Some(property) => Ok(property),
+            None => {
+                self.runtime_error(
+                    ErrorKind::PropertyError,
+                    &format!("Undefined property '{}' on {}", name, target),
+                    line,
+                )?;
+                unreachable!()
+            }
+        }
+    }
+
+    /// Calls `callee` from inside an instruction and runs it to completion.
+    ///
+    /// Fibers cannot yield while such a call is in progress, because the
+    /// instruction that started it has to finish in the same context.
+    fn call_reentrant(
+        &mut self,
+        callee: Value,
+        args: Vec<Value>,
+        line: usize,
+    ) -> Result<Value, InterpretError> {
+        let base_depth = self.call_frame_stack.len();
+        let callee_index = self.stack.len();
+        let arg_count = args.len();
+
+        self.push_stack(callee);
+        self.stack.extend(args);
+        self.call(callee_index, arg_count, line)?;
+
+        if self.call_frame_stack.len() > base_depth {
+            self.reentrant_calls += 1;
+            let result = self.run(base_depth, self.fibers.len());
+            self.reentrant_calls -= 1;
+            result?;
+        }
+
+        self.pop_stack(line)
+    }
+
+    /// Calls the zero-argument iterator method `name` on a user iterator.
+    fn call_iterator_method(
+        &mut self,
+        instance: &Rc<RefCell<Instance>>,
+        name: &str,
+        line: usize,
+    ) -> Result<Value, InterpretError> {
+        let method = instance.borrow().class.borrow().methods.get(name).cloned();
+        let method = match method {
+            Some(method) => method,
+            None => {
+                self.runtime_error(
+                    ErrorKind::PropertyError,
+                    &format!(
+                        "Iterator {} has no '{}' method",
+                        instance.borrow().class.borrow().name,
+                        name
+                    ),
+                    line,
+                )?;
+                unreachable!()
+            }
+        };
+
+        let bound_method = BoundMethod::new(method, Value::Instance(instance.clone()));
+        self.call_reentrant(Value::bound_method(bound_method), vec![], line)
+    }
+
+    /// Resolves the iterator a `for-in` loop walks over `iterable`.
+    ///
+    /// Lists and strings get a native cursor, and enums walk their variants.
+    /// Instances whose class defines `iterator()` are asked for one; any other
+    /// instance is assumed to be an iterator already and must implement
+    /// `hasNext()` and `next()`.
+    fn make_iterator(&mut self, iterable: Value, line: usize) -> Result<Value, InterpretError> {
+        match iterable {
+            Value::List(list) => Ok(Value::iterator(NativeIterator::new(IteratorSource::List(
+                list,
+            )))),
+            Value::Map(map) => Ok(Value::iterator(NativeIterator::new(IteratorSource::Map(
+                map,
+            )))),
+            Value::Tuple(values) => Ok(Value::iterator(NativeIterator::new(
+                IteratorSource::Tuple(values),
+            ))),
+            Value::String(string) => Ok(Value::iterator(NativeIterator::new(
+                IteratorSource::String(string),
+            ))),
+            Value::Enum(enumeration) => Ok(Value::iterator(NativeIterator::new(
+                IteratorSource::Tuple(enumeration.variants.clone()),
+            ))),
+            Value::Range(range) if range.start.is_none() => {
+                self.runtime_error(
+                    ErrorKind::TypeError,
+                    &format!("Cannot iterate over a range without a start, got {}", range),
+                    line,
+                )?;
+                unreachable!()
+            }
+            Value::Range(range) => Ok(Value::iterator(NativeIterator::new(IteratorSource::Range(
+                range,
+            )))),
+            Value::Iterator(_) => Ok(iterable),
+            Value::Instance(instance) => {
+                let method = instance
+                    .borrow()
+                    .class
+                    .borrow()
+                    .methods
+                    .get("iterator")
+                    .cloned();
+
+                match method {
+                    Some(method) => {
+                        let bound_method = BoundMethod::new(method, Value::Instance(instance));
+                        let iterator =
+                            self.call_reentrant(Value::bound_method(bound_method), vec![], line)?;
+
+                        match iterator {
+                            Value::Instance(_) | Value::Iterator(_) => Ok(iterator),
+                            Value::List(_) | Value::Map(_) | Value::String(_) => {
+                                self.make_iterator(iterator, line)
+                            }
+                            _ => {
+                                self.runtime_error(
+                                    ErrorKind::TypeError,
+                                    &format!(
+                                        "iterator() must return an iterator, got {}",
+                                        iterator.type_name()
+                                    ),
+                                    line,
+                                )?;
+                                unreachable!()
+                            }
+                        }
+                    }
+                    None => Ok(Value::Instance(instance)),
+                }
+            }
+            _ => {
+                self.runtime_error(
+                    ErrorKind::TypeError,
+                    &format!("Cannot iterate over {}", iterable.type_name()),
+                    line,
+                )?;
+                unreachable!()
+            }
+        }
+    }
+
+    /// The key `value` is stored under in a map.
+    fn map_key(&mut self, value: &Value, line: usize) -> Result<SwitchKey, InterpretError> {
+        match SwitchKey::from_value(value) {
+            Some(key) => Ok(key),
+            None => {
+                self.runtime_error(
+                    ErrorKind::TypeError,
+                    &format!(
+                        "Map keys must be nil, booleans, numbers or strings, got {}",
+                        value.type_name()
+                    ),
+                    line,
+                )?;
+                unreachable!()
+            }
+        }
+    }
+
+    /// Checks the operands of a range literal; a nil bound was left out.
+    fn build_range(
+        &mut self,
+        start: &Value,
+        end: &Value,
+        step: &Value,
+        inclusive: bool,
+        line: usize,
+    ) -> Result<Range, InterpretError> {
+        let mut bounds = [None, None];
+        for (bound, value) in bounds.iter_mut().zip([start, end]) {
+            match value {
+                Value::Number(number) => *bound = Some(*number),
+                Value::Nil => {}
+                other => {
+                    self.runtime_error(
+                        ErrorKind::TypeError,
+                        &format!("Range bounds must be numbers, got {}", other.type_name()),
+                        line,
+                    )?;
+                }
+            }
+        }
+
+        let step = match step {
+            Value::Number(step) if *step == 0.0 => {
+                self.runtime_error(ErrorKind::ArithmeticError, "Range step can't be zero", line)?;
+                unreachable!()
+            }
+            Value::Number(step) => *step,
+            Value::Nil => 1.0,
+            other => {
+                self.runtime_error(
+                    ErrorKind::TypeError,
+                    &format!("Range step must be a number, got {}", other.type_name()),
+                    line,
+                )?;
+                unreachable!()
+            }
+        };
+
+        Ok(Range::new(bounds[0], bounds[1], step, inclusive))
+    }
+
+    /// The positions `range` selects from a sequence of `length` elements.
+    /// A missing start or end means the first or last element, and bounds
+    /// past the end are clamped.
+    fn slice_positions(
+        &mut self,
+        range: &Range,
+        length: usize,
+        line: usize,
+    ) -> Result<Vec<usize>, InterpretError> {
+        for bound in [range.start, range.end].iter().flatten() {
+            if bound.fract() != 0.0 {
+                self.runtime_error(
+                    ErrorKind::TypeError,
+                    &format!("Slice bounds must be integers, got {}", bound),
+                    line,
+                )?;
+            }
+            if *bound < 0.0 {
+                self.runtime_error(
+                    ErrorKind::IndexError,
+                    &format!("Slice bound {} out of range for length {}", bound, length),
+                    line,
+                )?;
+            }
+        }
+
+        if range.step < 0.0 || range.step.fract() != 0.0 {
+            self.runtime_error(
+                ErrorKind::TypeError,
+                &format!("Slice step must be a positive integer, got {}", range.step),
+                line,
+            )?;
+        }
+
+        let start = (range.start.unwrap_or(0.0) as usize).min(length);
+        let end = match range.end {
+            Some(end) => (end as usize)
+                .saturating_add(range.inclusive as usize)
+                .min(length),
+            None => length,
+        };
+
+        Ok((start..end).step_by(range.step as usize).collect())
+    }
+
+    /// Converts a numeric index into a position within a sequence of `length` elements.
+    fn list_position(
+        &mut self,
+        index: f64,
+        length: usize,
+        line: usize,
+    ) -> Result<usize, InterpretError> {
+        if index.fract() != 0.0 {
+            self.runtime_error(
+                ErrorKind::TypeError,
+                &format!("Index must be an integer, got {}", index),
+                line,
+            )?;
+        }
+
+        if index < 0.0 || index >= length as f64 {
+            self.runtime_error(
+                ErrorKind::IndexError,
+                &format!("Index {} out of range for length {}", index, length),
+                line,
+            )?;
+        }
+
+        Ok(index as usize)
+    }
+
+    fn call_closure(
+        &mut self,
+        closure: Rc<Closure>,
+        arg_count: usize,
+        line: usize,
+        callee_index: usize,
+    ) -> Result<(), InterpretError> {
+        let ip = self.bind_arguments(&closure.function, arg_count, callee_index, line)?;
+
+        self.call_frame_stack.push(CallFrame {
+            closure,
+            ip,
+            slot_start: callee_index,
+        });
+
+        Ok(())
+    }
+
+    /// Checks `arg_count` against the arity of `function` and returns the
+    /// instruction its frame starts at. Arguments beyond the optional ones are
+    /// collected into the rest parameter's list; missing optional ones are left
+    /// to the default values at the returned entry point.
+    fn bind_arguments(
+        &mut self,
+        function: &Function,
+        arg_count: usize,
+        callee_index: usize,
+        line: usize,
+    ) -> Result<usize, InterpretError> {
+        let arity = function.arity;
+
+        if !arity.accepts(arg_count) {
+            self.runtime_error(
+                ErrorKind::ArityError,
+                &format!("Expected {} arguments but got {}", arity, arg_count),
+                line,
+            )?;
+            unreachable!()
+        }
+
+        let supplied = arg_count - arity.required;
+        if supplied < arity.optional {
+            return Ok(function.entry_points[supplied]);
+        }
+
+        if arity.rest {
+            let rest = self
+                .stack
+                .split_off(callee_index + 1 + arity.required + arity.optional);
+            self.push_stack(Value::list(rest));
+        }
+
+        Ok(function.entry_points[arity.optional])
+    }
+
+    fn invoke_fiber_method(
+        &mut self,
+        fiber: Rc<RefCell<Fiber>>,
+        name: &str,
+        arg_count: usize,
+        receiver_index: usize,
+        line: usize,
+    ) -> InterpretResult {
+        let mut args = self.stack.split_off(receiver_index + 1);
+        self.stack.truncate(receiver_index);
+
+        match (name, arg_count) {
+            ("resume" | "try", 0 | 1) => {
+                let value = args.pop();
+                self.resume_fiber(fiber, value, name == "try", line)
+            }
+            ("resume" | "try", _) => self.runtime_error(
+                ErrorKind::ArityError,
+                &format!("Expected 0 or 1 arguments but got {}", arg_count),
+                line,
+            ),
+            ("isDone", 0) => {
+                let is_done = fiber.borrow().is_done();
+                self.push_stack(Value::Bool(is_done));
+                Ok(())
+            }
+            ("isDone", _) => self.runtime_error(
+                ErrorKind::ArityError,
+                &format!("Expected 0 arguments but got {}", arg_count),
+                line,
+            ),
+            _ => self.runtime_error(
+                ErrorKind::PropertyError,
+                &format!("Undefined property '{}'", member_name(name)),
+                line,
+            ),
+        }
+    }
+
+    /// Transfers control into `fiber`, delivering `value` either as the argument
+    /// of its function (first resume) or as the result of its pending `yield`.
+    fn resume_fiber(
+        &mut self,
+        fiber: Rc<RefCell<Fiber>>,
+        value: Option<Value>,
+        catches_errors: bool,
+        line: usize,
+    ) -> InterpretResult {
+        let (state, function) = {
+            let fiber = fiber.borrow();
+            (fiber.state, Rc::clone(&fiber.closure.function))
+        };
+
+        match state {
+            FiberState::Done => {
+                return self.runtime_error(
+                    ErrorKind::FiberError,
+                    "Cannot resume a finished fiber",
+                    line,
+                )
+            }
+            FiberState::Running => {
+                return self.runtime_error(
+                    ErrorKind::FiberError,
+                    "Cannot resume a fiber that is already running",
+                    line,
+                )
+            }
+            FiberState::New | FiberState::Suspended => {}
+        }
+
+        self.switch_context(&fiber);
+        {
+            let mut fiber = fiber.borrow_mut();
+            fiber.state = FiberState::Running;
+            fiber.reentrant_calls = self.reentrant_calls;
+            fiber.catches_errors = catches_errors;
+        }
+        self.fibers.push(fiber);
+
+        if state == FiberState::Suspended {
+            self.push_stack(value.unwrap_or(Value::Nil));
+        } else {
+            // The first resume calls the fiber's function with the value, if
+            // it takes a parameter. Without one, defaults apply where possible.
+            let arg_count = match value {
+                Some(value) if function.arity.accepts(1) => {
+                    self.push_stack(value);
+                    1
+                }
+                None if !function.arity.accepts(0) => {
+                    self.push_stack(Value::Nil);
+                    1
+                }
+                _ => 0,
+            };
+            let ip = self.bind_arguments(&function, arg_count, 0, line)?;
+            if let Some(frame) = self.call_frame_stack.last_mut() {
+                frame.ip = ip;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Transfers control from the running fiber back to its resumer, which
+    /// receives `value` as the result of its `resume` call.
+    fn suspend_fiber(&mut self, value: Value, state: FiberState, line: usize) -> InterpretResult {
+        let fiber = match self.fibers.last() {
+            Some(fiber) => Rc::clone(fiber),
+            None => {
+                return self.runtime_error(
+                    ErrorKind::FiberError,
+                    "Cannot yield outside of a fiber",
+                    line,
+                )
+            }
+        };
+
+        if fiber.borrow().reentrant_calls != self.reentrant_calls {
+            return self.runtime_error(
+                ErrorKind::FiberError,
+                "Cannot yield across a native call boundary",
+                line,
+            );
+        }
+
+        self.fibers.pop();
+
+        self.switch_context(&fiber);
+        fiber.borrow_mut().state = state;
+        self.push_stack(value);
+
+        Ok(())
+    }
+
+    /// Unwinds every running fiber after a runtime error so the VM is left in
+    /// the main context.
+    fn abandon_fibers(&mut self) {
+        while let Some(fiber) = self.fibers.pop() {
+            self.switch_context(&fiber);
+            fiber.borrow_mut().state = FiberState::Done;
+        }
+    }
+
+    fn switch_context(&mut self, fiber: &Rc<RefCell<Fiber>>) {
+        {
+            let mut fiber = fiber.borrow_mut();
+            mem::swap(&mut self.stack, &mut fiber.stack);
+            mem::swap(&mut self.call_frame_stack, &mut fiber.call_frame_stack);
+            mem::swap(&mut self.open_upvalues, &mut fiber.open_upvalues);
+        }
+
+        // Closures running in another fiber may have assigned to upvalues that
+        // are still open on this stack; bring the aliased slots up to date.
+        for upvalue in &self.open_upvalues {
+            if let Some(stack_index) = *upvalue.stack_index.borrow() {
+                self.stack[stack_index] = upvalue.location.borrow().clone();
+            }
+        }
+    }
+
+    fn next_instruction(&mut self) -> Option<&Instruction> {
+        self.call_frame_stack.last_mut()?.next_instruction()
+    }
+
+    fn peek_instruction(&self) -> Option<&Instruction> {
+        self.call_frame_stack.last()?.peek_instruction()
+    }
+
+    fn offset(&mut self, offset: usize) {
+        if let Some(frame) = self.call_frame_stack.last_mut() {
+            frame.offset(offset);
+        }
     }
 
     fn offset_backward(&mut self, offset: usize) {
@@ -688,7 +2626,9 @@ impl VM {
         }
     }
 
-    fn binary_op<F>(&mut self, op: F, line: usize) -> InterpretResult
+    /// Applies `op` to two numbers, or calls the operator method `method`
+    /// when the left operand is an instance defining it.
+    fn binary_op<F>(&mut self, op: F, method: &str, line: usize) -> InterpretResult
     where
         F: Fn(f64, f64) -> Result<Value, InterpretError>,
     {
@@ -697,7 +2637,91 @@ impl VM {
                 self.push_stack(op(a, b)?);
                 Ok(())
             }
-            (_, _) => self.runtime_error("Operands must be numbers", line),
+            (b, a) => {
+                if self.call_operator(method, &[a, b], line)? {
+                    Ok(())
+                } else {
+                    self.runtime_error(ErrorKind::TypeError, "Operands must be numbers", line)
+                }
+            }
+        }
+    }
+
+    /// Calls the operator method `name` when the first of `operands` is an
+    /// instance whose class defines it, so `a + b` runs `a.__add__(b)`. The
+    /// call's result takes the operands' place on the stack. Returns `false`,
+    /// leaving the operands popped, when there is no such method.
+    fn call_operator(
+        &mut self,
+        name: &str,
+        operands: &[Value],
+        line: usize,
+    ) -> Result<bool, InterpretError> {
+        let method = match operands.first() {
+            Some(Value::Instance(instance)) => {
+                instance.borrow().class.borrow().methods.get(name).cloned()
+            }
+            _ => None,
+        };
+
+        match method {
+            Some(method) => {
+                let receiver_index = self.stack.len();
+                self.stack.extend_from_slice(operands);
+                self.call_closure(method, operands.len() - 1, line, receiver_index)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Like `binary_op`, for operators defined on integers only. Operands
+    /// must be integral numbers within the range of an `i64`.
+    fn integer_op<F>(&mut self, op: F, line: usize) -> InterpretResult
+    where
+        F: Fn(i64, i64) -> Result<i64, String>,
+    {
+        match (self.pop_stack(line)?, self.pop_stack(line)?) {
+            (Value::Number(b), Value::Number(a)) => {
+                match (Self::to_integer(a), Self::to_integer(b)) {
+                    (Some(a), Some(b)) => match op(a, b) {
+                        Ok(result) => {
+                            self.push_stack(Value::Number(result as f64));
+                            Ok(())
+                        }
+                        Err(message) => {
+                            self.runtime_error(ErrorKind::ArithmeticError, &message, line)
+                        }
+                    },
+                    _ => self.runtime_error(
+                        ErrorKind::TypeError,
+                        &format!("Operands must be integers, got {} and {}", a, b),
+                        line,
+                    ),
+                }
+            }
+            (_, _) => self.runtime_error(ErrorKind::TypeError, "Operands must be numbers", line),
+        }
+    }
+
+    fn to_integer(value: f64) -> Option<i64> {
+        const LIMIT: f64 = 9_223_372_036_854_775_808.0; // 2^63
+
+        if value.fract() == 0.0 && (-LIMIT..LIMIT).contains(&value) {
+            Some(value as i64)
+        } else {
+            None
+        }
+    }
+
+    fn shift_amount(amount: i64) -> Result<i64, String> {
+        if (0..64).contains(&amount) {
+            Ok(amount)
+        } else {
+            Err(format!(
+                "Shift amount must be between 0 and 63, got {}",
+                amount
+            ))
         }
     }
 
@@ -705,7 +2729,7 @@ impl VM {
         match self.stack.pop() {
             Some(value) => Ok(value),
             None => {
-                self.runtime_error("Stack is empty, cannot pop", line)?;
+                self.runtime_error(ErrorKind::Error, "Stack is empty, cannot pop", line)?;
                 unreachable!()
             }
         }
@@ -733,30 +2757,130 @@ impl VM {
         self.stack.push(value)
     }
 
-    fn runtime_error(&mut self, message: &str, line: usize) -> InterpretResult {
-        let stacktrace = self
+    fn runtime_error(&mut self, kind: ErrorKind, message: &str, line: usize) -> InterpretResult {
+        let stack_trace = self.stack_trace();
+        self.error = Some(self.new_error_with_trace(kind, message, line, &stack_trace));
+
+        Err(InterpretError::RuntimeError(
+            format!("{}\n{}", message, stack_trace.join("\n")),
+            line,
+        ))
+    }
+
+    /// Creates an instance of the built-in error class for `kind`, as if it had
+    /// been constructed by the instruction currently executing.
+    pub fn new_error(&self, kind: ErrorKind, message: &str) -> Value {
+        self.new_error_with_trace(kind, message, self.current_line(), &self.stack_trace())
+    }
+
+    /// Returns the `Error` instance describing `error`.
+    ///
+    /// Runtime errors raised by this VM keep the instance they were created
+    /// with, so its class tells which kind of error occurred.
+    pub fn error_value(&mut self, error: &InterpretError) -> Value {
+        match error {
+            InterpretError::RuntimeError(message, line) => match self.error.take() {
+                Some(value) => value,
+                None => self.new_error_with_trace(ErrorKind::Error, message, *line, &[]),
+            },
+            InterpretError::CompileError(message) => {
+                self.new_error_with_trace(ErrorKind::Error, message, 0, &[])
+            }
+        }
+    }
+
+    fn new_error_with_trace(
+        &self,
+        kind: ErrorKind,
+        message: &str,
+        line: usize,
+        stack_trace: &[String],
+    ) -> Value {
+        let class = match self.error_classes.get(&kind) {
+            Some(class) => Rc::clone(class),
+            // Only reachable while the prelude itself is being run.
+            None => Rc::new(RefCell::new(Class::new(kind.class_name().to_string()))),
+        };
+
+        let mut instance = Instance::new(class);
+        instance.set_field(
+            "message".to_string(),
+            Value::String(Rc::new(message.to_string())),
+        );
+        Self::set_error_location(&mut instance, line, stack_trace);
+
+        Value::instance(instance)
+    }
+
+    fn set_error_location(instance: &mut Instance, line: usize, stack_trace: &[String]) {
+        instance.set_field("line".to_string(), Value::Number(line as f64));
+        instance.set_field(
+            "stackTrace".to_string(),
+            Value::list(
+                stack_trace
+                    .iter()
+                    .map(|frame| Value::String(Rc::new(frame.clone())))
+                    .collect(),
+            ),
+        );
+    }
+
+    fn is_error_class(&self, class: &Class) -> bool {
+        self.error_classes
+            .get(&ErrorKind::Error)
+            .is_some_and(|error_class| class.is_subclass_of(error_class))
+    }
+
+    /// The line of the instruction the innermost frame is executing.
+    fn current_line(&self) -> usize {
+        self.call_frame_stack
+            .last()
+            .and_then(|frame| {
+                frame
+                    .closure
+                    .function
+                    .chunk
+                    .get_instruction(frame.ip.saturating_sub(1))
+            })
+            .map(|instruction| instruction.line)
+            .unwrap_or(0)
+    }
+
+    fn stack_trace(&self) -> Vec<String> {
+        let format_frame = |frame: &CallFrame| {
+            format!(
+                "[line {}] in {}",
+                frame
+                    .closure
+                    .function
+                    .chunk
+                    .get_instruction(frame.ip)
+                    .map(|instruction| instruction.line)
+                    .unwrap_or(0),
+                frame.closure.function.name
+            )
+        };
+
+        let mut stack_trace = self
             .call_frame_stack
             .iter()
-            .map(|frame| {
-                format!(
-                    "[line {}] in {}",
-                    frame
-                        .closure
-                        .function
-                        .chunk
-                        .get_instruction(frame.ip)
-                        .map(|instruction| instruction.line)
-                        .unwrap_or(0),
-                    frame.closure.function.name
-                )
-            })
             .rev()
+            .map(format_frame)
             .collect::<Vec<String>>();
 
-        Err(InterpretError::RuntimeError(
-            format!("{}\n{}", message, stacktrace.join("\n")),
-            line,
-        ))
+        // Running fibers hold their resumers' frames, so the trace continues there.
+        for fiber in self.fibers.iter().rev() {
+            stack_trace.extend(
+                fiber
+                    .borrow()
+                    .call_frame_stack
+                    .iter()
+                    .rev()
+                    .map(format_frame),
+            );
+        }
+
+        stack_trace
     }
 
     fn to_absolute_index(&self, index: usize) -> usize {
@@ -765,7 +2889,7 @@ impl VM {
             return index + shift;
         }
 
-        return index;
+        index
     }
 
     fn capture_upvalue(&mut self, index: usize) -> Result<Rc<Upvalue>, InterpretError> {
@@ -774,7 +2898,11 @@ impl VM {
         let stack_value = match self.stack.get(absolute_index) {
             Some(value) => value.clone(),
             None => {
-                self.runtime_error("Invalid stack index for upvalue capture", 0)?;
+                self.runtime_error(
+                    ErrorKind::Error,
+                    "Invalid stack index for upvalue capture",
+                    0,
+                )?;
                 unreachable!()
             }
         };
@@ -0,0 +1,70 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn abstract_as_name() {
+    assert_eq!(
+        interpret_file_stdout("examples/abstract/abstract_as_name.lox"),
+        "9\n\"a method named abstract\"\n"
+    );
+}
+
+#[test]
+fn abstract_init() {
+    assert!(interpret_file_result("examples/abstract/abstract_init.lox").is_err());
+}
+
+#[test]
+fn getter() {
+    assert_eq!(interpret_file_stdout("examples/abstract/getter.lox"), "1\n");
+}
+
+#[test]
+fn implemented() {
+    assert_eq!(
+        interpret_file_stdout("examples/abstract/implemented.lox"),
+        "\"area 9\"\n"
+    );
+}
+
+#[test]
+fn inherited_implementation() {
+    assert_eq!(
+        interpret_file_stdout("examples/abstract/inherited_implementation.lox"),
+        "\"middle\"\n"
+    );
+}
+
+#[test]
+fn instantiate_base() {
+    assert!(interpret_file_result("examples/abstract/instantiate_base.lox").is_err());
+}
+
+#[test]
+fn missing() {
+    assert!(interpret_file_result("examples/abstract/missing.lox").is_err());
+}
+
+#[test]
+fn missing_semicolon() {
+    assert!(interpret_file_result("examples/abstract/missing_semicolon.lox").is_err());
+}
+
+#[test]
+fn redeclared() {
+    assert!(interpret_file_result("examples/abstract/redeclared.lox").is_err());
+}
+
+#[test]
+fn static_methods() {
+    assert_eq!(
+        interpret_file_stdout("examples/abstract/static_methods.lox"),
+        "\"shapes have an area\"\n[]\n"
+    );
+}
+
+#[test]
+fn trait_conformance() {
+    assert!(interpret_file_result("examples/abstract/trait_conformance.lox").is_err());
+}
@@ -0,0 +1,71 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn callable_getter() {
+    assert_eq!(
+        interpret_file_stdout("examples/accessor/callable_getter.lox"),
+        "15\n11\n"
+    );
+}
+
+#[test]
+fn getter() {
+    assert_eq!(
+        interpret_file_stdout("examples/accessor/getter.lox"),
+        "12\n27\n"
+    );
+}
+
+#[test]
+fn inherited() {
+    assert_eq!(
+        interpret_file_stdout("examples/accessor/inherited.lox"),
+        "\"square, shape s\"\n4\n4\n\"shape plain\"\n"
+    );
+}
+
+#[test]
+fn init_getter() {
+    assert!(interpret_file_result("examples/accessor/init_getter.lox").is_err());
+}
+
+#[test]
+fn read_only() {
+    assert!(interpret_file_result("examples/accessor/read_only.lox").is_err());
+}
+
+#[test]
+fn setter() {
+    assert_eq!(
+        interpret_file_stdout("examples/accessor/setter.lox"),
+        "212\n100\n0\n"
+    );
+}
+
+#[test]
+fn setter_arity() {
+    assert!(interpret_file_result("examples/accessor/setter_arity.lox").is_err());
+}
+
+#[test]
+fn setter_return() {
+    assert_eq!(
+        interpret_file_stdout("examples/accessor/setter_return.lox"),
+        "0\n7\n7\n"
+    );
+}
+
+#[test]
+fn setter_return_value() {
+    assert!(interpret_file_result("examples/accessor/setter_return_value.lox").is_err());
+}
+
+#[test]
+fn yield_in_getter() {
+    assert_eq!(
+        interpret_file_stdout("examples/accessor/yield_in_getter.lox"),
+        "\"computing\"\n42\n"
+    );
+}
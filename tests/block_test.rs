@@ -17,3 +17,11 @@ fn scope() {
         "\"inner\"\n\"outer\"\n"
     );
 }
+
+#[test]
+fn in_function_loop() {
+    assert_eq!(
+        interpret_file_stdout("examples/block/in_function_loop.lox"),
+        "0\n1\n2\n"
+    );
+}
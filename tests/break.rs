@@ -0,0 +1,66 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn closes_upvalues() {
+    assert_eq!(
+        interpret_file_stdout("examples/break/closes_upvalues.lox"),
+        "0\n1\n"
+    );
+}
+
+#[test]
+fn in_for() {
+    assert_eq!(
+        interpret_file_stdout("examples/break/in_for.lox"),
+        "0\n1\n\"once\"\n"
+    );
+}
+
+#[test]
+fn in_for_in() {
+    assert_eq!(
+        interpret_file_stdout("examples/break/in_for_in.lox"),
+        "\"a\"\n\"b\"\n"
+    );
+}
+
+#[test]
+fn in_function_in_loop() {
+    assert!(interpret_file_result("examples/break/in_function_in_loop.lox").is_err());
+}
+
+#[test]
+fn in_match() {
+    assert_eq!(
+        interpret_file_stdout("examples/break/in_match.lox"),
+        "0\n2\n1\n3\n5\n\"zero\"\n"
+    );
+}
+
+#[test]
+fn in_while() {
+    assert_eq!(
+        interpret_file_stdout("examples/break/in_while.lox"),
+        "0\n1\n2\n\"done\"\n"
+    );
+}
+
+#[test]
+fn nested() {
+    assert_eq!(interpret_file_stdout("examples/break/nested.lox"), "0\n1\n");
+}
+
+#[test]
+fn outside_loop() {
+    assert!(interpret_file_result("examples/break/outside_loop.lox").is_err());
+}
+
+#[test]
+fn pops_locals() {
+    assert_eq!(
+        interpret_file_stdout("examples/break/pops_locals.lox"),
+        "\"before\"\n\"after\"\n"
+    );
+}
@@ -20,6 +20,14 @@ fn inherited_method() {
     );
 }
 
+#[test]
+fn keyword_member_names() {
+    assert_eq!(
+        interpret_file_stdout("examples/class/keyword_member_names.lox"),
+        "1\n2\n\"static method\"\n\"own with\"\n\"own with\"\n\"getter in\"\n5\n\"base match 3\"\n"
+    );
+}
+
 #[test]
 fn local_inherit_other() {
     assert_eq!(
@@ -1,6 +1,6 @@
 mod test_utils;
 
-use test_utils::{interpret_file_result, interpret_file_stdout};
+use test_utils::interpret_file_stdout;
 
 #[test]
 fn assign_to_closure() {
@@ -105,3 +105,11 @@ fn unused_later_closure() {
         "\"a\"\n"
     );
 }
+
+#[test]
+fn reuse_upvalue() {
+    assert_eq!(
+        interpret_file_stdout("examples/closure/reuse_upvalue.lox"),
+        "\"a\"\n\"b\"\n\"a\"\n"
+    );
+}
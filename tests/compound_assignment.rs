@@ -0,0 +1,84 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn global() {
+    assert_eq!(
+        interpret_file_stdout("examples/compound_assignment/global.lox"),
+        "30\n\"Hello, world\"\n"
+    );
+}
+
+#[test]
+fn increment() {
+    assert_eq!(
+        interpret_file_stdout("examples/compound_assignment/increment.lox"),
+        "0\n1\n2\n2\n0\n1.5\n2.5\n1.5\n0\n1\n2\n"
+    );
+}
+
+#[test]
+fn increment_upvalue() {
+    assert_eq!(
+        interpret_file_stdout("examples/compound_assignment/increment_upvalue.lox"),
+        "2\n6\n"
+    );
+}
+
+#[test]
+fn invalid_increment_target() {
+    assert!(
+        interpret_file_result("examples/compound_assignment/invalid_increment_target.lox").is_err()
+    );
+}
+
+#[test]
+fn invalid_target() {
+    assert!(interpret_file_result("examples/compound_assignment/invalid_target.lox").is_err());
+}
+
+#[test]
+fn local() {
+    assert_eq!(
+        interpret_file_stdout("examples/compound_assignment/local.lox"),
+        "15\n12\n24\n3\n1\n2\n"
+    );
+}
+
+#[test]
+fn non_number() {
+    assert!(interpret_file_result("examples/compound_assignment/non_number.lox").is_err());
+}
+
+#[test]
+fn property() {
+    assert_eq!(
+        interpret_file_stdout("examples/compound_assignment/property.lox"),
+        "5\n20\n"
+    );
+}
+
+#[test]
+fn property_evaluated_once() {
+    assert_eq!(
+        interpret_file_stdout("examples/compound_assignment/property_evaluated_once.lox"),
+        "11\n1\n13\n3\n"
+    );
+}
+
+#[test]
+fn subscript() {
+    assert_eq!(
+        interpret_file_stdout("examples/compound_assignment/subscript.lox"),
+        "[1, 42, 3]\n[0, 42, 3]\n42\n4\n[0, 43, 4]\n2\n"
+    );
+}
+
+#[test]
+fn upvalue() {
+    assert_eq!(
+        interpret_file_stdout("examples/compound_assignment/upvalue.lox"),
+        "5\n"
+    );
+}
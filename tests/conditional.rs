@@ -0,0 +1,94 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn coalesce() {
+    assert_eq!(
+        interpret_file_stdout("examples/conditional/coalesce.lox"),
+        "\"default\"\n\"value\"\nfalse\n0\n3\n8080\n"
+    );
+}
+
+#[test]
+fn coalesce_short_circuit() {
+    assert_eq!(
+        interpret_file_stdout("examples/conditional/coalesce_short_circuit.lox"),
+        "1\n\"evaluated\"\n2\n"
+    );
+}
+
+#[test]
+fn optional_chaining() {
+    assert_eq!(
+        interpret_file_stdout("examples/conditional/optional_chaining.lox"),
+        "\"a\"\n\"b\"\nnil\n\"node a\"\nnil\n\"fallback\"\n"
+    );
+}
+
+#[test]
+fn optional_chaining_assignment() {
+    assert!(
+        interpret_file_result("examples/conditional/optional_chaining_assignment.lox").is_err()
+    );
+}
+
+#[test]
+fn optional_chaining_non_instance() {
+    assert!(
+        interpret_file_result("examples/conditional/optional_chaining_non_instance.lox").is_err()
+    );
+}
+
+#[test]
+fn optional_chaining_short_circuit() {
+    assert_eq!(
+        interpret_file_stdout("examples/conditional/optional_chaining_short_circuit.lox"),
+        "\"y\"\nnil\n"
+    );
+}
+
+#[test]
+fn ternary() {
+    assert_eq!(
+        interpret_file_stdout("examples/conditional/ternary.lox"),
+        "\"yes\"\n\"no\"\n2\n1\n\"big\"\n"
+    );
+}
+
+#[test]
+fn ternary_in_locals() {
+    assert_eq!(
+        interpret_file_stdout("examples/conditional/ternary_in_locals.lox"),
+        "\"positive\"\n3\n"
+    );
+}
+
+#[test]
+fn ternary_missing_colon() {
+    assert!(interpret_file_result("examples/conditional/ternary_missing_colon.lox").is_err());
+}
+
+#[test]
+fn ternary_nested() {
+    assert_eq!(
+        interpret_file_stdout("examples/conditional/ternary_nested.lox"),
+        "\"negative\"\n\"zero\"\n\"positive\"\n2\n"
+    );
+}
+
+#[test]
+fn ternary_precedence() {
+    assert_eq!(
+        interpret_file_stdout("examples/conditional/ternary_precedence.lox"),
+        "2\n\"different\"\n"
+    );
+}
+
+#[test]
+fn ternary_short_circuit() {
+    assert_eq!(
+        interpret_file_stdout("examples/conditional/ternary_short_circuit.lox"),
+        "\"then\"\n\"else\"\n"
+    );
+}
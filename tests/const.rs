@@ -0,0 +1,87 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn assign_error_value() {
+    assert_eq!(
+        interpret_file_stdout("examples/const/assign_error_value.lox"),
+        "instance NameError\n\"Cannot assign to constant 'LIMIT'\"\n10\n"
+    );
+}
+
+#[test]
+fn assign_global() {
+    assert!(interpret_file_result("examples/const/assign_global.lox").is_err());
+}
+
+#[test]
+fn assign_global_in_function() {
+    assert!(interpret_file_result("examples/const/assign_global_in_function.lox").is_err());
+}
+
+#[test]
+fn assign_local() {
+    assert!(interpret_file_result("examples/const/assign_local.lox").is_err());
+}
+
+#[test]
+fn assign_nested_upvalue() {
+    assert!(interpret_file_result("examples/const/assign_nested_upvalue.lox").is_err());
+}
+
+#[test]
+fn assign_upvalue() {
+    assert!(interpret_file_result("examples/const/assign_upvalue.lox").is_err());
+}
+
+#[test]
+fn closure() {
+    assert_eq!(interpret_file_stdout("examples/const/closure.lox"), "4\n");
+}
+
+#[test]
+fn compound_local() {
+    assert!(interpret_file_result("examples/const/compound_local.lox").is_err());
+}
+
+#[test]
+fn global() {
+    assert_eq!(
+        interpret_file_stdout("examples/const/global.lox"),
+        "10\n\"hello world\"\ntrue\n"
+    );
+}
+
+#[test]
+fn increment_local() {
+    assert!(interpret_file_result("examples/const/increment_local.lox").is_err());
+}
+
+#[test]
+fn local() {
+    assert_eq!(interpret_file_stdout("examples/const/local.lox"), "1\n2\n");
+}
+
+#[test]
+fn missing_initializer() {
+    assert!(interpret_file_result("examples/const/missing_initializer.lox").is_err());
+}
+
+#[test]
+fn prefix_increment_upvalue() {
+    assert!(interpret_file_result("examples/const/prefix_increment_upvalue.lox").is_err());
+}
+
+#[test]
+fn redefine_global() {
+    assert!(interpret_file_result("examples/const/redefine_global.lox").is_err());
+}
+
+#[test]
+fn shadow() {
+    assert_eq!(
+        interpret_file_stdout("examples/const/shadow.lox"),
+        "\"changed\"\n\"outer\"\n"
+    );
+}
@@ -0,0 +1,56 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn closes_upvalues() {
+    assert_eq!(
+        interpret_file_stdout("examples/continue/closes_upvalues.lox"),
+        "10\n20\n30\n"
+    );
+}
+
+#[test]
+fn for_runs_increment() {
+    assert_eq!(
+        interpret_file_stdout("examples/continue/for_runs_increment.lox"),
+        "0\n2\n4\n"
+    );
+}
+
+#[test]
+fn in_for_in() {
+    assert_eq!(
+        interpret_file_stdout("examples/continue/in_for_in.lox"),
+        "1\n3\n4\n"
+    );
+}
+
+#[test]
+fn in_if_else() {
+    assert_eq!(
+        interpret_file_stdout("examples/continue/in_if_else.lox"),
+        "0\n2\n3\n\"four\"\n5\n"
+    );
+}
+
+#[test]
+fn in_while() {
+    assert_eq!(
+        interpret_file_stdout("examples/continue/in_while.lox"),
+        "1\n3\n5\n"
+    );
+}
+
+#[test]
+fn outside_loop() {
+    assert!(interpret_file_result("examples/continue/outside_loop.lox").is_err());
+}
+
+#[test]
+fn pops_locals() {
+    assert_eq!(
+        interpret_file_stdout("examples/continue/pops_locals.lox"),
+        "10\n\"after\"\n"
+    );
+}
@@ -0,0 +1,107 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn arity_error_value() {
+    assert_eq!(
+        interpret_file_stdout("examples/destructuring/arity_error_value.lox"),
+        "instance ArityError\n\"Expected 2 values to unpack but got 3\"\n"
+    );
+}
+
+#[test]
+fn closure() {
+    assert_eq!(
+        interpret_file_stdout("examples/destructuring/closure.lox"),
+        "10\n"
+    );
+}
+
+#[test]
+fn const_names() {
+    assert!(interpret_file_result("examples/destructuring/const_names.lox").is_err());
+}
+
+#[test]
+fn duplicate_name() {
+    assert!(interpret_file_result("examples/destructuring/duplicate_name.lox").is_err());
+}
+
+#[test]
+fn fields() {
+    assert_eq!(
+        interpret_file_stdout("examples/destructuring/fields.lox"),
+        "3\n4\n1\n3\n"
+    );
+}
+
+#[test]
+fn fields_missing() {
+    assert!(interpret_file_result("examples/destructuring/fields_missing.lox").is_err());
+}
+
+#[test]
+fn list() {
+    assert_eq!(
+        interpret_file_stdout("examples/destructuring/list.lox"),
+        "\"a\"\n\"b\"\n\"c\"\n3\n"
+    );
+}
+
+#[test]
+fn locals() {
+    assert_eq!(
+        interpret_file_stdout("examples/destructuring/locals.lox"),
+        "\"before\"\n2\n1\n\"after\"\n"
+    );
+}
+
+#[test]
+fn multiple_return() {
+    assert_eq!(
+        interpret_file_stdout("examples/destructuring/multiple_return.lox"),
+        "(3, 2)\n3\n2\n3\n2\n"
+    );
+}
+
+#[test]
+fn not_unpackable() {
+    assert!(interpret_file_result("examples/destructuring/not_unpackable.lox").is_err());
+}
+
+#[test]
+fn rest() {
+    assert_eq!(
+        interpret_file_stdout("examples/destructuring/rest.lox"),
+        "1\n[2, 3]\n1\n[]\n"
+    );
+}
+
+#[test]
+fn rest_not_last() {
+    assert!(interpret_file_result("examples/destructuring/rest_not_last.lox").is_err());
+}
+
+#[test]
+fn too_few() {
+    assert!(interpret_file_result("examples/destructuring/too_few.lox").is_err());
+}
+
+#[test]
+fn too_many() {
+    assert!(interpret_file_result("examples/destructuring/too_many.lox").is_err());
+}
+
+#[test]
+fn tuple() {
+    assert_eq!(
+        interpret_file_stdout("examples/destructuring/tuple.lox"),
+        "(1, \"one\")\n(42,)\n3\ntrue\nfalse\nfalse\ntrue\n3\n4\n"
+    );
+}
+
+#[test]
+fn tuple_immutable() {
+    assert!(interpret_file_result("examples/destructuring/tuple_immutable.lox").is_err());
+}
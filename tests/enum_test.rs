@@ -0,0 +1,94 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn associated_values() {
+    assert_eq!(
+        interpret_file_stdout("examples/enum/associated_values.lox"),
+        "Shape.Circle(2)\nShape.Rect(3, 4)\nShape.Empty\n2\n12\n\"Rect\"\ntrue\nfalse\nfalse\nShape.Circle\nfalse\n"
+    );
+}
+
+#[test]
+fn basic() {
+    assert_eq!(
+        interpret_file_stdout("examples/enum/basic.lox"),
+        "Color\nColor.Red\n\"Green\"\ntrue\nfalse\ntrue\n"
+    );
+}
+
+#[test]
+fn call_plain_variant() {
+    assert!(interpret_file_result("examples/enum/call_plain_variant.lox").is_err());
+}
+
+#[test]
+fn constructor_arity() {
+    assert!(interpret_file_result("examples/enum/constructor_arity.lox").is_err());
+}
+
+#[test]
+fn distinct_enums() {
+    assert_eq!(
+        interpret_file_stdout("examples/enum/distinct_enums.lox"),
+        "false\ntrue\nfalse\ntrue\nfalse\n"
+    );
+}
+
+#[test]
+fn duplicate_field() {
+    assert!(interpret_file_result("examples/enum/duplicate_field.lox").is_err());
+}
+
+#[test]
+fn duplicate_variant() {
+    assert!(interpret_file_result("examples/enum/duplicate_variant.lox").is_err());
+}
+
+#[test]
+fn iterate() {
+    assert_eq!(
+        interpret_file_stdout("examples/enum/iterate.lox"),
+        "Direction.North\nDirection.East\nDirection.South\nDirection.West\n4\n"
+    );
+}
+
+#[test]
+fn local() {
+    assert_eq!(
+        interpret_file_stdout("examples/enum/local.lox"),
+        "Coin.Tails\ntrue\n"
+    );
+}
+
+#[test]
+fn lookup() {
+    assert_eq!(
+        interpret_file_stdout("examples/enum/lookup.lox"),
+        "Status.Active\ntrue\nnil\nStatus.Suspended\n"
+    );
+}
+
+#[test]
+fn lookup_non_string() {
+    assert!(interpret_file_result("examples/enum/lookup_non_string.lox").is_err());
+}
+
+#[test]
+fn match_variants() {
+    assert_eq!(
+        interpret_file_stdout("examples/enum/match_variants.lox"),
+        "\"warm\"\n\"cool\"\n12\n9\n\"color\"\n\"shape\"\n\"other\"\n"
+    );
+}
+
+#[test]
+fn undefined_field() {
+    assert!(interpret_file_result("examples/enum/undefined_field.lox").is_err());
+}
+
+#[test]
+fn undefined_variant() {
+    assert!(interpret_file_result("examples/enum/undefined_variant.lox").is_err());
+}
@@ -0,0 +1,179 @@
+mod test_utils;
+
+use rox::error::ErrorKind;
+use rox::function::NativeError;
+use rox::run::run;
+use rox::value::Value;
+use rox::vm::VM;
+use test_utils::{capture_stdout, interpret_file_result, interpret_file_stdout};
+
+fn field(value: &Value, name: &str) -> Value {
+    match value {
+        Value::Instance(instance) => instance.borrow().fields[name].clone(),
+        _ => panic!("Expected an instance, got {}", value),
+    }
+}
+
+#[test]
+fn fields() {
+    assert_eq!(
+        interpret_file_stdout("examples/error/fields.lox"),
+        "\"boom\"\n1\ninstance Error\n"
+    );
+}
+
+#[test]
+fn result_value() {
+    assert_eq!(
+        interpret_file_stdout("examples/error/result_value.lox"),
+        "30\ninstance TypeError\n\"Age must not be negative\"\n"
+    );
+}
+
+#[test]
+fn resume_propagates() {
+    assert!(interpret_file_result("examples/error/resume_propagates.lox").is_err());
+}
+
+#[test]
+fn stack_trace() {
+    assert_eq!(
+        interpret_file_stdout("examples/error/stack_trace.lox"),
+        "[\"[line 2] in validate\", \"[line 7] in check\", \"[line 10] in <script>\"]\n"
+    );
+}
+
+#[test]
+fn subclass() {
+    assert_eq!(
+        interpret_file_stdout("examples/error/subclass.lox"),
+        "\"Missing config.lox\"\n\"config.lox\"\n8\n"
+    );
+}
+
+#[test]
+fn try_inner_fiber() {
+    assert_eq!(
+        interpret_file_stdout("examples/error/try_inner_fiber.lox"),
+        "1\ninstance TypeError\ntrue\ntrue\n\"after\"\n"
+    );
+}
+
+#[test]
+fn try_iterator() {
+    assert_eq!(
+        interpret_file_stdout("examples/error/try_iterator.lox"),
+        "instance TypeError\n3\n"
+    );
+}
+
+#[test]
+fn try_kinds() {
+    assert_eq!(
+        interpret_file_stdout("examples/error/try_kinds.lox"),
+        "instance NameError\ninstance IndexError\ninstance ArithmeticError\ninstance ArityError\ninstance TypeError\n"
+    );
+}
+
+#[test]
+fn try_native() {
+    assert_eq!(
+        interpret_file_stdout("examples/error/try_native.lox"),
+        "instance TypeError\ninstance TypeError\ninstance PropertyError\ninstance ArityError\n\"len expects a list, map, tuple, string, enum or range, got number\"\n"
+    );
+}
+
+#[test]
+fn try_nested_calls() {
+    assert_eq!(
+        interpret_file_stdout("examples/error/try_nested_calls.lox"),
+        "\"Only instances have properties. Expected instance, got nil\"\n[\"[line 2] in inner\", \"[line 6] in outer\", \"[line 9] in <anonymous line 9>\", \"[line 10] in <script>\"]\n"
+    );
+}
+
+#[test]
+fn try_running() {
+    assert_eq!(
+        interpret_file_stdout("examples/error/try_running.lox"),
+        "instance FiberError\n"
+    );
+}
+
+#[test]
+fn try_runtime_error() {
+    assert_eq!(
+        interpret_file_stdout("examples/error/try_runtime_error.lox"),
+        "instance TypeError\n\"Operands must be numbers or strings, found: 1 and nil\"\n3\ntrue\n"
+    );
+}
+
+#[test]
+fn try_success() {
+    assert_eq!(
+        interpret_file_stdout("examples/error/try_success.lox"),
+        "8\n11\ntrue\n"
+    );
+}
+
+#[test]
+fn prelude_defines_error_classes() {
+    for kind in ErrorKind::ALL {
+        let output = capture_stdout(|| {
+            let code = format!(
+                "var error = {}(\"m\");\nprint error is Error;",
+                kind.class_name()
+            );
+            run(code, &mut VM::new()).unwrap();
+        });
+        assert_eq!(output, "true\n", "{}", kind.class_name());
+    }
+}
+
+#[test]
+fn host_error_value() {
+    let mut vm = VM::new();
+    let error = run("var list = [1];\nlist[3];".to_string(), &mut vm).unwrap_err();
+    let value = vm.error_value(&error);
+
+    assert_eq!(value.to_string(), "instance IndexError");
+    assert_eq!(
+        field(&value, "message").to_string(),
+        "\"Index 3 out of range for length 1\""
+    );
+    assert_eq!(field(&value, "line").to_string(), "2");
+}
+
+#[test]
+fn native_constructs_error() {
+    fn lookup(vm: &mut VM, args: Vec<Value>) -> Result<Value, NativeError> {
+        Ok(vm.new_error(ErrorKind::NameError, &format!("No key {}", args[0])))
+    }
+
+    let output = capture_stdout(|| {
+        let mut vm = VM::new();
+        vm.define_native("lookup", lookup);
+        run(
+            "var error = lookup(42);\nprint error;\nprint error.message;\nprint error.line;"
+                .to_string(),
+            &mut vm,
+        )
+        .unwrap();
+    });
+
+    assert_eq!(output, "instance NameError\n\"No key 42\"\n1\n");
+}
+
+#[test]
+fn native_raises_error_kind() {
+    fn lookup(_vm: &mut VM, args: Vec<Value>) -> Result<Value, NativeError> {
+        Err((ErrorKind::NameError, format!("No key {}", args[0])))
+    }
+
+    let mut vm = VM::new();
+    vm.define_native("lookup", lookup);
+    let error = run("lookup(42);".to_string(), &mut vm).unwrap_err();
+    let value = vm.error_value(&error);
+
+    assert_eq!(value.to_string(), "instance NameError");
+    assert_eq!(field(&value, "message").to_string(), "\"No key 42\"");
+}
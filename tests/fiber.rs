@@ -0,0 +1,82 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn error_in_fiber() {
+    assert_eq!(
+        interpret_file_stdout("examples/fiber/error_in_fiber.lox"),
+        "1\n"
+    );
+    assert!(interpret_file_result("examples/fiber/error_in_fiber.lox").is_err());
+}
+
+#[test]
+fn generator() {
+    assert_eq!(
+        interpret_file_stdout("examples/fiber/generator.lox"),
+        "1\n2\n3\nfalse\nnil\ntrue\n"
+    );
+}
+
+#[test]
+fn nested_fibers() {
+    assert_eq!(
+        interpret_file_stdout("examples/fiber/nested_fibers.lox"),
+        "\"inner 1\"\n\"outer\"\n\"inner 2\"\n"
+    );
+}
+
+#[test]
+fn resume_finished() {
+    assert!(interpret_file_result("examples/fiber/resume_finished.lox").is_err());
+}
+
+#[test]
+fn resume_running() {
+    assert!(interpret_file_result("examples/fiber/resume_running.lox").is_err());
+}
+
+#[test]
+fn resume_value() {
+    assert_eq!(
+        interpret_file_stdout("examples/fiber/resume_value.lox"),
+        "1\n\"a\"\n2\n\"b\"\n3\n\"done\"\n"
+    );
+}
+
+#[test]
+fn shared_upvalue() {
+    assert_eq!(
+        interpret_file_stdout("examples/fiber/shared_upvalue.lox"),
+        "2\n11\n\"after\"\n"
+    );
+}
+
+#[test]
+fn too_many_parameters() {
+    assert!(interpret_file_result("examples/fiber/too_many_parameters.lox").is_err());
+}
+
+#[test]
+fn yield_across_native_call() {
+    assert!(interpret_file_result("examples/fiber/yield_across_native_call.lox").is_err());
+}
+
+#[test]
+fn yield_at_top_level() {
+    assert!(interpret_file_result("examples/fiber/yield_at_top_level.lox").is_err());
+}
+
+#[test]
+fn yield_from_nested_call() {
+    assert_eq!(
+        interpret_file_stdout("examples/fiber/yield_from_nested_call.lox"),
+        "1\n2\n3\n"
+    );
+}
+
+#[test]
+fn yield_outside_fiber() {
+    assert!(interpret_file_result("examples/fiber/yield_outside_fiber.lox").is_err());
+}
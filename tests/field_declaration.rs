@@ -0,0 +1,74 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn before_init() {
+    assert_eq!(
+        interpret_file_stdout("examples/field_declaration/before_init.lox"),
+        "\"origin\"\n3\n0\n"
+    );
+}
+
+#[test]
+fn defaults() {
+    assert_eq!(
+        interpret_file_stdout("examples/field_declaration/defaults.lox"),
+        "2\n[\"count\", \"step\"]\n"
+    );
+}
+
+#[test]
+fn fresh_per_instance() {
+    assert_eq!(
+        interpret_file_stdout("examples/field_declaration/fresh_per_instance.lox"),
+        "[1]\n[]\n"
+    );
+}
+
+#[test]
+fn in_trait() {
+    assert!(interpret_file_result("examples/field_declaration/in_trait.lox").is_err());
+}
+
+#[test]
+fn inherited() {
+    assert_eq!(
+        interpret_file_stdout("examples/field_declaration/inherited.lox"),
+        "\"derived\"\n1\n[\"kind\", \"size\", \"color\"]\n"
+    );
+}
+
+#[test]
+fn initializer_error() {
+    assert!(interpret_file_result("examples/field_declaration/initializer_error.lox").is_err());
+}
+
+#[test]
+fn missing_semicolon() {
+    assert!(interpret_file_result("examples/field_declaration/missing_semicolon.lox").is_err());
+}
+
+#[test]
+fn no_initializer() {
+    assert_eq!(
+        interpret_file_stdout("examples/field_declaration/no_initializer.lox"),
+        "nil\n"
+    );
+}
+
+#[test]
+fn private_field() {
+    assert_eq!(
+        interpret_file_stdout("examples/field_declaration/private_field.lox"),
+        "100\n"
+    );
+}
+
+#[test]
+fn uses_this() {
+    assert_eq!(
+        interpret_file_stdout("examples/field_declaration/uses_this.lox"),
+        "68\n"
+    );
+}
@@ -0,0 +1,111 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn closure_per_iteration() {
+    assert_eq!(
+        interpret_file_stdout("examples/for_in/closure_per_iteration.lox"),
+        "1\n2\n3\n"
+    );
+}
+
+#[test]
+fn empty() {
+    assert_eq!(
+        interpret_file_stdout("examples/for_in/empty.lox"),
+        "\"done\"\n"
+    );
+}
+
+#[test]
+fn in_fiber() {
+    assert_eq!(
+        interpret_file_stdout("examples/for_in/in_fiber.lox"),
+        "\"a\"\n\"b\"\n"
+    );
+}
+
+#[test]
+fn iterator_returns_list() {
+    assert_eq!(
+        interpret_file_stdout("examples/for_in/iterator_returns_list.lox"),
+        "\"a\"\n\"b\"\n"
+    );
+}
+
+#[test]
+fn list() {
+    assert_eq!(
+        interpret_file_stdout("examples/for_in/list.lox"),
+        "1\nnil\n\"three\"\n"
+    );
+}
+
+#[test]
+fn locals_in_function() {
+    assert_eq!(
+        interpret_file_stdout("examples/for_in/locals_in_function.lox"),
+        "12\n"
+    );
+}
+
+#[test]
+fn loop_variable_scope() {
+    assert!(interpret_file_result("examples/for_in/loop_variable_scope.lox").is_err());
+}
+
+#[test]
+fn map() {
+    assert_eq!(
+        interpret_file_stdout("examples/for_in/map.lox"),
+        "\"b=2\"\n\"a=1\"\n\"c=3\"\n"
+    );
+}
+
+#[test]
+fn missing_has_next() {
+    assert!(interpret_file_result("examples/for_in/missing_has_next.lox").is_err());
+}
+
+#[test]
+fn missing_next() {
+    assert!(interpret_file_result("examples/for_in/missing_next.lox").is_err());
+}
+
+#[test]
+fn nested() {
+    assert_eq!(
+        interpret_file_stdout("examples/for_in/nested.lox"),
+        "1\n2\n3\n"
+    );
+}
+
+#[test]
+fn nil_element() {
+    assert_eq!(
+        interpret_file_stdout("examples/for_in/nil_element.lox"),
+        "1\nnil\n3\n"
+    );
+}
+
+#[test]
+fn not_iterable() {
+    assert!(interpret_file_result("examples/for_in/not_iterable.lox").is_err());
+}
+
+#[test]
+fn string() {
+    assert_eq!(
+        interpret_file_stdout("examples/for_in/string.lox"),
+        "\"a\"\n\"ñ\"\n\"b\"\n"
+    );
+}
+
+#[test]
+fn user_iterator() {
+    assert_eq!(
+        interpret_file_stdout("examples/for_in/user_iterator.lox"),
+        "3\n2\n1\n2\n1\n"
+    );
+}
@@ -0,0 +1,80 @@
+mod test_utils;
+
+use rox::vm::InterpretError;
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn argument() {
+    assert_eq!(
+        interpret_file_stdout("examples/lambda/argument.lox"),
+        "[1, 4, 9]\n"
+    );
+}
+
+#[test]
+fn arrow_body() {
+    assert_eq!(
+        interpret_file_stdout("examples/lambda/arrow_body.lox"),
+        "42\n"
+    );
+}
+
+#[test]
+fn arrow_on_declaration() {
+    assert!(interpret_file_result("examples/lambda/arrow_on_declaration.lox").is_err());
+}
+
+#[test]
+fn block_body() {
+    assert_eq!(
+        interpret_file_stdout("examples/lambda/block_body.lox"),
+        "3\n"
+    );
+}
+
+#[test]
+fn closure() {
+    assert_eq!(
+        interpret_file_stdout("examples/lambda/closure.lox"),
+        "1\n2\n15\n"
+    );
+}
+
+#[test]
+fn immediately_invoked() {
+    assert_eq!(
+        interpret_file_stdout("examples/lambda/immediately_invoked.lox"),
+        "\"called\"\n"
+    );
+}
+
+#[test]
+fn missing_body() {
+    assert!(interpret_file_result("examples/lambda/missing_body.lox").is_err());
+}
+
+#[test]
+fn no_return_value() {
+    assert_eq!(
+        interpret_file_stdout("examples/lambda/no_return_value.lox"),
+        "nil\n"
+    );
+}
+
+#[test]
+fn print() {
+    assert_eq!(
+        interpret_file_stdout("examples/lambda/print.lox"),
+        "fn <anonymous line 1>\n"
+    );
+}
+
+#[test]
+fn stack_trace() {
+    match interpret_file_result("examples/lambda/stack_trace.lox") {
+        Err(InterpretError::RuntimeError(message, _)) => {
+            assert!(message.contains("[line 2] in <anonymous line 1>"))
+        }
+        result => panic!("expected runtime error, got {:?}", result),
+    }
+}
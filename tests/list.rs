@@ -0,0 +1,50 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn index() {
+    assert_eq!(
+        interpret_file_stdout("examples/list/index.lox"),
+        "\"a\"\n\"c\"\n[\"a\", \"B\", \"c\"]\n\"é\"\n"
+    );
+}
+
+#[test]
+fn index_non_list() {
+    assert!(interpret_file_result("examples/list/index_non_list.lox").is_err());
+}
+
+#[test]
+fn index_not_integer() {
+    assert!(interpret_file_result("examples/list/index_not_integer.lox").is_err());
+}
+
+#[test]
+fn index_out_of_range() {
+    assert!(interpret_file_result("examples/list/index_out_of_range.lox").is_err());
+}
+
+#[test]
+fn len_and_push() {
+    assert_eq!(
+        interpret_file_stdout("examples/list/len_and_push.lox"),
+        "[1, 2]\n2\n3\n"
+    );
+}
+
+#[test]
+fn literal() {
+    assert_eq!(
+        interpret_file_stdout("examples/list/literal.lox"),
+        "[]\n[1, \"two\", nil, [true]]\n"
+    );
+}
+
+#[test]
+fn shared_reference() {
+    assert_eq!(
+        interpret_file_stdout("examples/list/shared_reference.lox"),
+        "[1, 2]\n"
+    );
+}
@@ -33,3 +33,19 @@ fn or_truth() {
         "\"ok\"\n\"ok\"\ntrue\n0\n\"s\"\n"
     );
 }
+
+#[test]
+fn and_in_locals() {
+    assert_eq!(
+        interpret_file_stdout("examples/logical_operator/and_in_locals.lox"),
+        "1\nfalse\n3\n"
+    );
+}
+
+#[test]
+fn or_in_locals() {
+    assert_eq!(
+        interpret_file_stdout("examples/logical_operator/or_in_locals.lox"),
+        "1\n2\n3\n"
+    );
+}
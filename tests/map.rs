@@ -0,0 +1,45 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn index() {
+    assert_eq!(
+        interpret_file_stdout("examples/map/index.lox"),
+        "36\nnil\n[\"Ada\": 37, \"Grace\": 85]\n"
+    );
+}
+
+#[test]
+fn invalid_key() {
+    assert!(interpret_file_result("examples/map/invalid_key.lox").is_err());
+}
+
+#[test]
+fn literal() {
+    assert_eq!(
+        interpret_file_stdout("examples/map/literal.lox"),
+        "[:]\n[\"a\": 1, \"b\": [2, 3]]\n[1: \"one\", true: nil, nil: false]\n2\n"
+    );
+}
+
+#[test]
+fn missing_colon() {
+    assert!(interpret_file_result("examples/map/missing_colon.lox").is_err());
+}
+
+#[test]
+fn number_keys() {
+    assert_eq!(
+        interpret_file_stdout("examples/map/number_keys.lox"),
+        "\"one\"\n\"zero\"\n2\n"
+    );
+}
+
+#[test]
+fn shared_reference() {
+    assert_eq!(
+        interpret_file_stdout("examples/map/shared_reference.lox"),
+        "[\"x\": 1, \"y\": 2]\ntrue\nfalse\n[\"x\": 1, \"y\": 2, \"self\": [...]]\n"
+    );
+}
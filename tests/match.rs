@@ -0,0 +1,121 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn binding() {
+    assert_eq!(
+        interpret_file_stdout("examples/match/binding.lox"),
+        "42\n\"value!\"\n\"before\"\n\"after\"\n"
+    );
+}
+
+#[test]
+fn binding_closure() {
+    assert_eq!(
+        interpret_file_stdout("examples/match/binding_closure.lox"),
+        "\"none\"\n10\n20\n"
+    );
+}
+
+#[test]
+fn class() {
+    assert_eq!(
+        interpret_file_stdout("examples/match/class.lox"),
+        "12\n9\n0\nnil\n"
+    );
+}
+
+#[test]
+fn empty() {
+    assert!(interpret_file_result("examples/match/empty.lox").is_err());
+}
+
+#[test]
+fn evaluated_once() {
+    assert_eq!(
+        interpret_file_stdout("examples/match/evaluated_once.lox"),
+        "\"other\"\n1\n"
+    );
+}
+
+#[test]
+fn guard() {
+    assert_eq!(
+        interpret_file_stdout("examples/match/guard.lox"),
+        "\"zero\"\n\"negative\"\n\"even\"\n\"odd\"\n"
+    );
+}
+
+#[test]
+fn guard_with_pattern() {
+    assert_eq!(
+        interpret_file_stdout("examples/match/guard_with_pattern.lox"),
+        "\"small\"\n\"small\"\n\"big\"\n"
+    );
+}
+
+#[test]
+fn in_loop() {
+    assert_eq!(
+        interpret_file_stdout("examples/match/in_loop.lox"),
+        "0\n2\n3\n"
+    );
+}
+
+#[test]
+fn invalid_pattern() {
+    assert!(interpret_file_result("examples/match/invalid_pattern.lox").is_err());
+}
+
+#[test]
+fn invalid_range() {
+    assert!(interpret_file_result("examples/match/invalid_range.lox").is_err());
+}
+
+#[test]
+fn literal() {
+    assert_eq!(
+        interpret_file_stdout("examples/match/literal.lox"),
+        "\"zero\"\n\"one or two\"\n\"greeting\"\n\"yes\"\n\"nothing\"\n\"something else\"\n\"zero\"\n"
+    );
+}
+
+#[test]
+fn negative_literal() {
+    assert_eq!(
+        interpret_file_stdout("examples/match/negative_literal.lox"),
+        "\"negative\"\n"
+    );
+}
+
+#[test]
+fn non_exhaustive() {
+    assert!(interpret_file_result("examples/match/non_exhaustive.lox").is_err());
+}
+
+#[test]
+fn non_exhaustive_error() {
+    assert_eq!(
+        interpret_file_stdout("examples/match/non_exhaustive_error.lox"),
+        "instance MatchError\n\"No match arm for value 3\"\n2\n"
+    );
+}
+
+#[test]
+fn not_a_class() {
+    assert!(interpret_file_result("examples/match/not_a_class.lox").is_err());
+}
+
+#[test]
+fn range() {
+    assert_eq!(
+        interpret_file_stdout("examples/match/range.lox"),
+        "\"A\"\n\"A\"\n\"B\"\n\"C\"\n\"invalid\"\n\"invalid\"\n"
+    );
+}
+
+#[test]
+fn unreachable_arm() {
+    assert!(interpret_file_result("examples/match/unreachable_arm.lox").is_err());
+}
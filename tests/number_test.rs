@@ -1,12 +1,100 @@
 mod test_utils;
 
+use rox::vm::InterpretError;
 use test_utils::{interpret_file_result, interpret_file_stdout};
 
+fn assert_compile_error(path: &str, expected: &str) {
+    match interpret_file_result(path) {
+        Err(InterpretError::CompileError(message)) => assert_eq!(message, expected),
+        result => panic!("Expected a compile error, got {:?}", result),
+    }
+}
+
+#[test]
+fn binary() {
+    assert_eq!(
+        interpret_file_stdout("examples/number/binary.lox"),
+        "10\n1\n240\n"
+    );
+}
+
+#[test]
+fn binary_invalid_digit() {
+    assert_compile_error(
+        "examples/number/binary_invalid_digit.lox",
+        "[line 2] Error: Invalid digit in binary literal.",
+    );
+}
+
+#[test]
+fn binary_without_digits() {
+    assert_compile_error(
+        "examples/number/binary_without_digits.lox",
+        "[line 2] Error: Expect binary digits after '0b'.",
+    );
+}
+
 #[test]
 fn decimal_point_at_eof() {
     assert!(interpret_file_result("examples/number/decimal_point_at_eof.lox").is_err());
 }
 
+#[test]
+fn double_separator() {
+    assert_compile_error(
+        "examples/number/double_separator.lox",
+        "[line 2] Error: Digit separator '_' must be between digits.",
+    );
+}
+
+#[test]
+fn exponent() {
+    assert_eq!(
+        interpret_file_stdout("examples/number/exponent.lox"),
+        "0.000000001\n602000000000000000000000\n1000\n250\n100000000000\n"
+    );
+}
+
+#[test]
+fn exponent_sign_without_digits() {
+    assert_compile_error(
+        "examples/number/exponent_sign_without_digits.lox",
+        "[line 2] Error: Expect digits in exponent.",
+    );
+}
+
+#[test]
+fn exponent_without_digits() {
+    assert_compile_error(
+        "examples/number/exponent_without_digits.lox",
+        "[line 2] Error: Expect digits in exponent.",
+    );
+}
+
+#[test]
+fn hex() {
+    assert_eq!(
+        interpret_file_stdout("examples/number/hex.lox"),
+        "255\n0\n3735928559\n65535\n-16\n"
+    );
+}
+
+#[test]
+fn hex_invalid_digit() {
+    assert_compile_error(
+        "examples/number/hex_invalid_digit.lox",
+        "[line 2] Error: Invalid digit in hexadecimal literal.",
+    );
+}
+
+#[test]
+fn hex_without_digits() {
+    assert_compile_error(
+        "examples/number/hex_without_digits.lox",
+        "[line 2] Error: Expect hexadecimal digits after '0x'.",
+    );
+}
+
 #[test]
 fn leading_dot() {
     assert!(interpret_file_result("examples/number/leading_dot.lox").is_err());
@@ -28,7 +116,39 @@ fn literals() {
 //     );
 // }
 
+#[test]
+fn separator_after_prefix() {
+    assert_compile_error(
+        "examples/number/separator_after_prefix.lox",
+        "[line 2] Error: Digit separator '_' must be between digits.",
+    );
+}
+
+#[test]
+fn separator_before_dot() {
+    assert_compile_error(
+        "examples/number/separator_before_dot.lox",
+        "[line 2] Error: Digit separator '_' must be between digits.",
+    );
+}
+
+#[test]
+fn separators() {
+    assert_eq!(
+        interpret_file_stdout("examples/number/separators.lox"),
+        "1000000\n3.141592\n10.01\n"
+    );
+}
+
 #[test]
 fn trailing_dot() {
     assert!(interpret_file_result("examples/number/trailing_dot.lox").is_err());
 }
+
+#[test]
+fn trailing_separator() {
+    assert_compile_error(
+        "examples/number/trailing_separator.lox",
+        "[line 2] Error: Digit separator '_' must be between digits.",
+    );
+}
@@ -74,21 +74,21 @@ fn equals() {
     );
 }
 
-// #[test]
-// fn equals_class() {
-//     assert_eq!(
-//         interpret_file_stdout("examples/operator/equals_class.lox"),
-//         "true\nfalse\nfalse\ntrue\nfalse\nfalse\nfalse\nfalse\n"
-//     );
-// }
-
-// #[test]
-// fn equals_method() {
-//     assert_eq!(
-//         interpret_file_stdout("examples/operator/equals_method.lox"),
-//         "true\nfalse\n"
-//     );
-// }
+#[test]
+fn equals_class() {
+    assert_eq!(
+        interpret_file_stdout("examples/operator/equals_class.lox"),
+        "true\nfalse\nfalse\ntrue\nfalse\nfalse\nfalse\nfalse\n"
+    );
+}
+
+#[test]
+fn equals_method() {
+    assert_eq!(
+        interpret_file_stdout("examples/operator/equals_method.lox"),
+        "true\nfalse\n"
+    );
+}
 
 #[test]
 fn greater_nonnum_num() {
@@ -202,3 +202,83 @@ fn subtract_nonnum_num() {
 fn subtract_num_nonnum() {
     assert!(interpret_file_result("examples/operator/subtract_num_nonnum.lox").is_err());
 }
+
+#[test]
+fn bit_not_non_integral() {
+    assert!(interpret_file_result("examples/operator/bit_not_non_integral.lox").is_err());
+}
+
+#[test]
+fn bit_not_nonnum() {
+    assert!(interpret_file_result("examples/operator/bit_not_nonnum.lox").is_err());
+}
+
+#[test]
+fn bitwise() {
+    assert_eq!(
+        interpret_file_stdout("examples/operator/bitwise.lox"),
+        "8\n14\n6\n-1\n-6\n1024\n255\n-4\n"
+    );
+}
+
+#[test]
+fn bitwise_non_integral() {
+    assert!(interpret_file_result("examples/operator/bitwise_non_integral.lox").is_err());
+}
+
+#[test]
+fn bitwise_precedence() {
+    assert_eq!(
+        interpret_file_stdout("examples/operator/bitwise_precedence.lox"),
+        "1\n8\n4\ntrue\ntrue\n"
+    );
+}
+
+#[test]
+fn integer_divide() {
+    assert_eq!(
+        interpret_file_stdout("examples/operator/integer_divide.lox"),
+        "3\n-3\n3\n-7\n"
+    );
+}
+
+#[test]
+fn integer_divide_by_zero() {
+    assert!(interpret_file_result("examples/operator/integer_divide_by_zero.lox").is_err());
+}
+
+#[test]
+fn modulo() {
+    assert_eq!(
+        interpret_file_stdout("examples/operator/modulo.lox"),
+        "1\n-1\n1\n1.5\n5\n"
+    );
+}
+
+#[test]
+fn modulo_by_zero() {
+    assert!(interpret_file_result("examples/operator/modulo_by_zero.lox").is_err());
+}
+
+#[test]
+fn modulo_nonnum() {
+    assert!(interpret_file_result("examples/operator/modulo_nonnum.lox").is_err());
+}
+
+#[test]
+fn power() {
+    assert_eq!(
+        interpret_file_stdout("examples/operator/power.lox"),
+        "1024\n512\n64\n-4\n0.5\n12\n2\n"
+    );
+}
+
+#[test]
+fn power_nonnum() {
+    assert!(interpret_file_result("examples/operator/power_nonnum.lox").is_err());
+}
+
+#[test]
+fn shift_out_of_range() {
+    assert!(interpret_file_result("examples/operator/shift_out_of_range.lox").is_err());
+}
@@ -0,0 +1,71 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn arithmetic() {
+    assert_eq!(
+        interpret_file_stdout("examples/overloading/arithmetic.lox"),
+        "4\n6\n2\n6\n1.5\n-1\n4\n"
+    );
+}
+
+#[test]
+fn call() {
+    assert_eq!(
+        interpret_file_stdout("examples/overloading/call.lox"),
+        "15\n3\n6\n"
+    );
+}
+
+#[test]
+fn comparison() {
+    assert_eq!(
+        interpret_file_stdout("examples/overloading/comparison.lox"),
+        "true\nfalse\nfalse\nfalse\ntrue\nfalse\ntrue\ntrue\n"
+    );
+}
+
+#[test]
+fn default_equality() {
+    assert_eq!(
+        interpret_file_stdout("examples/overloading/default_equality.lox"),
+        "true\nfalse\n"
+    );
+}
+
+#[test]
+fn error_in_operator() {
+    assert!(interpret_file_result("examples/overloading/error_in_operator.lox").is_err());
+}
+
+#[test]
+fn index() {
+    assert_eq!(
+        interpret_file_stdout("examples/overloading/index.lox"),
+        "3\n2\n"
+    );
+}
+
+#[test]
+fn match_literal() {
+    assert_eq!(
+        interpret_file_stdout("examples/overloading/match_literal.lox"),
+        "\"one\"\n\"zero or one\"\n\"one\"\n"
+    );
+}
+
+#[test]
+fn missing_method() {
+    assert!(interpret_file_result("examples/overloading/missing_method.lox").is_err());
+}
+
+#[test]
+fn not_callable() {
+    assert!(interpret_file_result("examples/overloading/not_callable.lox").is_err());
+}
+
+#[test]
+fn right_operand() {
+    assert!(interpret_file_result("examples/overloading/right_operand.lox").is_err());
+}
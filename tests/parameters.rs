@@ -0,0 +1,155 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn arity_at_least() {
+    assert!(interpret_file_result("examples/parameters/arity_at_least.lox").is_err());
+}
+
+#[test]
+fn arity_range() {
+    assert!(interpret_file_result("examples/parameters/arity_range.lox").is_err());
+}
+
+#[test]
+fn arity_spread() {
+    assert!(interpret_file_result("examples/parameters/arity_spread.lox").is_err());
+}
+
+#[test]
+fn default() {
+    assert_eq!(
+        interpret_file_stdout("examples/parameters/default.lox"),
+        "\"Hello, Ada\"\n\"Hi, Ada\"\nnil\n"
+    );
+}
+
+#[test]
+fn default_and_rest() {
+    assert_eq!(
+        interpret_file_stdout("examples/parameters/default_and_rest.lox"),
+        "[1, \"b\", []]\n[1, 2, []]\n[1, 2, [3, 4]]\n"
+    );
+}
+
+#[test]
+fn default_closure() {
+    assert_eq!(
+        interpret_file_stdout("examples/parameters/default_closure.lox"),
+        "\"hello world\"\n\"hello lox\"\n"
+    );
+}
+
+#[test]
+fn default_evaluated_per_call() {
+    assert_eq!(
+        interpret_file_stdout("examples/parameters/default_evaluated_per_call.lox"),
+        "1\n1\n0\n1\n2\n"
+    );
+}
+
+#[test]
+fn default_method() {
+    assert_eq!(
+        interpret_file_stdout("examples/parameters/default_method.lox"),
+        "1\n4\n15\n"
+    );
+}
+
+#[test]
+fn default_self_reference() {
+    assert!(interpret_file_result("examples/parameters/default_self_reference.lox").is_err());
+}
+
+#[test]
+fn default_uses_earlier() {
+    assert_eq!(
+        interpret_file_stdout("examples/parameters/default_uses_earlier.lox"),
+        "0\n10\n2\n0\n20\n1\n"
+    );
+}
+
+#[test]
+fn fiber_default() {
+    assert_eq!(
+        interpret_file_stdout("examples/parameters/fiber_default.lox"),
+        "100\n[5, []]\n"
+    );
+}
+
+#[test]
+fn native_arity() {
+    assert!(interpret_file_result("examples/parameters/native_arity.lox").is_err());
+}
+
+#[test]
+fn native_arity_error() {
+    assert_eq!(
+        interpret_file_stdout("examples/parameters/native_arity_error.lox"),
+        "instance ArityError\n\"Expected at least 2 arguments but got 1\"\n"
+    );
+}
+
+#[test]
+fn required_after_default() {
+    assert!(interpret_file_result("examples/parameters/required_after_default.lox").is_err());
+}
+
+#[test]
+fn rest() {
+    assert_eq!(
+        interpret_file_stdout("examples/parameters/rest.lox"),
+        "0\n1\n10\n\"a\"\n[1, 2]\n\"b\"\n[]\n"
+    );
+}
+
+#[test]
+fn rest_not_last() {
+    assert!(interpret_file_result("examples/parameters/rest_not_last.lox").is_err());
+}
+
+#[test]
+fn spread() {
+    assert_eq!(
+        interpret_file_stdout("examples/parameters/spread.lox"),
+        "6\n60\n6\n15\n"
+    );
+}
+
+#[test]
+fn spread_method() {
+    assert_eq!(
+        interpret_file_stdout("examples/parameters/spread_method.lox"),
+        "1\n6\n\"moved\"\n5\n"
+    );
+}
+
+#[test]
+fn spread_native() {
+    assert_eq!(
+        interpret_file_stdout("examples/parameters/spread_native.lox"),
+        "[\"a\", \"b\", \"c\"]\n4\n"
+    );
+}
+
+#[test]
+fn spread_not_list() {
+    assert!(interpret_file_result("examples/parameters/spread_not_list.lox").is_err());
+}
+
+#[test]
+fn spread_rest() {
+    assert_eq!(
+        interpret_file_stdout("examples/parameters/spread_rest.lox"),
+        "4\n"
+    );
+}
+
+#[test]
+fn spread_tuple() {
+    assert_eq!(
+        interpret_file_stdout("examples/parameters/spread_tuple.lox"),
+        "\"3 remainder 1\"\n\"1 remainder 2\"\n"
+    );
+}
@@ -0,0 +1,97 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn abstract_private() {
+    assert!(interpret_file_result("examples/private/abstract_private.lox").is_err());
+}
+
+#[test]
+fn assigned_later() {
+    assert_eq!(
+        interpret_file_stdout("examples/private/assigned_later.lox"),
+        "1\n"
+    );
+}
+
+#[test]
+fn fields() {
+    assert_eq!(
+        interpret_file_stdout("examples/private/fields.lox"),
+        "15\n[]\n"
+    );
+}
+
+#[test]
+fn inheritance() {
+    assert_eq!(
+        interpret_file_stdout("examples/private/inheritance.lox"),
+        "\"base\"\n\"derived\"\n"
+    );
+}
+
+#[test]
+fn methods() {
+    assert_eq!(
+        interpret_file_stdout("examples/private/methods.lox"),
+        "\"Hello, Ada\"\n[\"greet\", \"init\"]\n"
+    );
+}
+
+#[test]
+fn other_class() {
+    assert!(interpret_file_result("examples/private/other_class.lox").is_err());
+}
+
+#[test]
+fn outside_class() {
+    assert!(interpret_file_result("examples/private/outside_class.lox").is_err());
+}
+
+#[test]
+fn private_method_not_overridden() {
+    assert_eq!(
+        interpret_file_stdout("examples/private/private_method_not_overridden.lox"),
+        "\"base\"\n"
+    );
+}
+
+#[test]
+fn reflection() {
+    assert!(interpret_file_result("examples/private/reflection.lox").is_err());
+}
+
+#[test]
+fn same_class() {
+    assert_eq!(
+        interpret_file_stdout("examples/private/same_class.lox"),
+        "true\nfalse\n"
+    );
+}
+
+#[test]
+fn static_private() {
+    assert_eq!(
+        interpret_file_stdout("examples/private/static_private.lox"),
+        "2\n"
+    );
+}
+
+#[test]
+fn subclass_access() {
+    assert!(interpret_file_result("examples/private/subclass_access.lox").is_err());
+}
+
+#[test]
+fn trait_private() {
+    assert_eq!(
+        interpret_file_stdout("examples/private/trait_private.lox"),
+        "\"<tagged>\"\n"
+    );
+}
+
+#[test]
+fn undeclared() {
+    assert!(interpret_file_result("examples/private/undeclared.lox").is_err());
+}
@@ -0,0 +1,132 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn empty() {
+    assert_eq!(
+        interpret_file_stdout("examples/range/empty.lox"),
+        "\"done\"\n"
+    );
+}
+
+#[test]
+fn exclusive() {
+    assert_eq!(
+        interpret_file_stdout("examples/range/exclusive.lox"),
+        "0\n1\n2\n"
+    );
+}
+
+#[test]
+fn fractional_slice() {
+    assert!(interpret_file_result("examples/range/fractional_slice.lox").is_err());
+}
+
+#[test]
+fn huge_bound() {
+    assert_eq!(
+        interpret_file_stdout("examples/range/huge_bound.lox"),
+        "[2, 3]\n[2, 3]\n[]\n\"a\"\ninstance ArithmeticError\ninstance ArithmeticError\n0\n0\n1000000000000001\n"
+    );
+}
+
+#[test]
+fn inclusive() {
+    assert_eq!(
+        interpret_file_stdout("examples/range/inclusive.lox"),
+        "1\n2\n3\n"
+    );
+}
+
+#[test]
+fn list_slice() {
+    assert_eq!(
+        interpret_file_stdout("examples/range/list_slice.lox"),
+        "[20, 30, 40]\n[20, 30]\n[30, 40, 50]\n[10, 20]\n[10, 30, 50]\n[40, 50]\n[]\n5\n"
+    );
+}
+
+#[test]
+fn match_patterns() {
+    assert_eq!(
+        interpret_file_stdout("examples/range/match_patterns.lox"),
+        "\"A\"\n\"B\"\n"
+    );
+}
+
+#[test]
+fn negative_slice() {
+    assert!(interpret_file_result("examples/range/negative_slice.lox").is_err());
+}
+
+#[test]
+fn no_start() {
+    assert!(interpret_file_result("examples/range/no_start.lox").is_err());
+}
+
+#[test]
+fn non_number_bound() {
+    assert!(interpret_file_result("examples/range/non_number_bound.lox").is_err());
+}
+
+#[test]
+fn open_end() {
+    assert_eq!(
+        interpret_file_stdout("examples/range/open_end.lox"),
+        "10\n11\n12\n"
+    );
+}
+
+#[test]
+fn precedence() {
+    assert_eq!(
+        interpret_file_stdout("examples/range/precedence.lox"),
+        "1..3\n8\n"
+    );
+}
+
+#[test]
+fn step() {
+    assert_eq!(
+        interpret_file_stdout("examples/range/step.lox"),
+        "0\n5\n10\n3\n2\n1\n0\n0.25\n0.5\n0.75\n"
+    );
+}
+
+#[test]
+fn step_variable() {
+    assert_eq!(
+        interpret_file_stdout("examples/range/step_variable.lox"),
+        "0..3\n0..<4\n0..3 step 2\n[20, 30, 40]\n[10, 30]\n"
+    );
+}
+
+#[test]
+fn string_slice() {
+    assert_eq!(
+        interpret_file_stdout("examples/range/string_slice.lox"),
+        "\"ell\"\n\"hell\"\n\"llo\"\n\"éllo\"\n\"wörld\"\n\"語テキ\"\n"
+    );
+}
+
+#[test]
+fn tuple_slice() {
+    assert_eq!(
+        interpret_file_stdout("examples/range/tuple_slice.lox"),
+        "(2, 3)\n"
+    );
+}
+
+#[test]
+fn value() {
+    assert_eq!(
+        interpret_file_stdout("examples/range/value.lox"),
+        "1..10\n0..<5 step 2\n\"range\"\n10\n4\n0\ntrue\nfalse\n"
+    );
+}
+
+#[test]
+fn zero_step() {
+    assert!(interpret_file_result("examples/range/zero_step.lox").is_err());
+}
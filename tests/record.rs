@@ -0,0 +1,103 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn arity() {
+    assert!(interpret_file_result("examples/record/arity.lox").is_err());
+}
+
+#[test]
+fn basic() {
+    assert_eq!(
+        interpret_file_stdout("examples/record/basic.lox"),
+        "1\n2\nPoint(x=1, y=2)\n\"at Point(x=1, y=2)\"\n"
+    );
+}
+
+#[test]
+fn copy_with() {
+    assert_eq!(
+        interpret_file_stdout("examples/record/copy_with.lox"),
+        "Point(x=5, y=2)\nPoint(x=1, y=2)\nPoint(x=3, y=4)\ntrue\n"
+    );
+}
+
+#[test]
+fn copy_with_odd_arguments() {
+    assert!(interpret_file_result("examples/record/copy_with_odd_arguments.lox").is_err());
+}
+
+#[test]
+fn copy_with_unknown_field() {
+    assert!(interpret_file_result("examples/record/copy_with_unknown_field.lox").is_err());
+}
+
+#[test]
+fn duplicate_field() {
+    assert!(interpret_file_result("examples/record/duplicate_field.lox").is_err());
+}
+
+#[test]
+fn empty() {
+    assert_eq!(
+        interpret_file_stdout("examples/record/empty.lox"),
+        "Unit()\ntrue\n"
+    );
+}
+
+#[test]
+fn equality() {
+    assert_eq!(
+        interpret_file_stdout("examples/record/equality.lox"),
+        "true\nfalse\nfalse\nfalse\nfalse\n"
+    );
+}
+
+#[test]
+fn inheritance() {
+    assert_eq!(
+        interpret_file_stdout("examples/record/inheritance.lox"),
+        "\"shape Circle(radius=2)\"\n"
+    );
+}
+
+#[test]
+fn local() {
+    assert_eq!(
+        interpret_file_stdout("examples/record/local.lox"),
+        "Pair(first=1, second=2)\nfalse\n"
+    );
+}
+
+#[test]
+fn nested() {
+    assert_eq!(
+        interpret_file_stdout("examples/record/nested.lox"),
+        "Line(start=Point(x=0, y=0), end=Point(x=1, y=1))\ntrue\n"
+    );
+}
+
+#[test]
+fn rebound_name() {
+    assert_eq!(
+        interpret_file_stdout("examples/record/rebound_name.lox"),
+        "true\nfalse\n"
+    );
+}
+
+#[test]
+fn record_as_name() {
+    assert_eq!(
+        interpret_file_stdout("examples/record/record_as_name.lox"),
+        "1\n2\n\"logged 3\"\n\"first\"\nEntry(record=first)\n"
+    );
+}
+
+#[test]
+fn with_body() {
+    assert_eq!(
+        interpret_file_stdout("examples/record/with_body.lox"),
+        "5\n(3, 4)\n"
+    );
+}
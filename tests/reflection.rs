@@ -0,0 +1,87 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn class_of() {
+    assert_eq!(
+        interpret_file_stdout("examples/reflection/class_of.lox"),
+        "Dog\ntrue\nnil\nAnimal\nnil\ninstance Dog\n"
+    );
+}
+
+#[test]
+fn field_access() {
+    assert_eq!(
+        interpret_file_stdout("examples/reflection/field_access.lox"),
+        "false\ntrue\ntrue\ntrue\ntrue\n4\n"
+    );
+}
+
+#[test]
+fn field_name_not_string() {
+    assert!(interpret_file_result("examples/reflection/field_name_not_string.lox").is_err());
+}
+
+#[test]
+fn field_on_non_instance() {
+    assert!(interpret_file_result("examples/reflection/field_on_non_instance.lox").is_err());
+}
+
+#[test]
+fn fields() {
+    assert_eq!(
+        interpret_file_stdout("examples/reflection/fields.lox"),
+        "[\"y\", \"x\", \"label\"]\n[\"y\", \"x\", \"label\"]\ntrue\nfalse\n[\"x\", \"label\"]\n[\"x\", \"label\", \"y\"]\n"
+    );
+}
+
+#[test]
+fn functions() {
+    assert_eq!(
+        interpret_file_stdout("examples/reflection/functions.lox"),
+        "2\n1\n2\n0\n1\n\"add\"\n\"Point\"\n\"length\"\n\"len\"\n"
+    );
+}
+
+#[test]
+fn get_missing_field() {
+    assert!(interpret_file_result("examples/reflection/get_missing_field.lox").is_err());
+}
+
+#[test]
+fn is_non_class() {
+    assert!(interpret_file_result("examples/reflection/is_non_class.lox").is_err());
+}
+
+#[test]
+fn is_operator() {
+    assert_eq!(
+        interpret_file_stdout("examples/reflection/is_operator.lox"),
+        "true\ntrue\nfalse\nfalse\ntrue\ntrue\ntrue\nfalse\n"
+    );
+}
+
+#[test]
+fn methods() {
+    assert_eq!(
+        interpret_file_stdout("examples/reflection/methods.lox"),
+        "[\"init\", \"speak\"]\n[\"init\", \"run\", \"speak\"]\n"
+    );
+}
+
+#[test]
+fn serialize() {
+    assert_eq!(
+        interpret_file_stdout("examples/reflection/serialize.lox"),
+        "\"User { name: Ada, address: Address { city: London } }\"\n"
+    );
+}
+
+#[test]
+fn type_of() {
+    assert_eq!(
+        interpret_file_stdout("examples/reflection/type_of.lox"),
+        "\"number\"\n\"string\"\n\"nil\"\n\"bool\"\n\"list\"\n\"tuple\"\n\"class\"\n\"instance\"\n\"bound method\"\n\"closure\"\n\"native function\"\n\"enum\"\n\"variant\"\n"
+    );
+}
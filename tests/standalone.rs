@@ -4,10 +4,7 @@ use test_utils::{interpret_file_result, interpret_file_stdout};
 
 #[test]
 fn empty_file() {
-    assert_eq!(
-        interpret_file_stdout("examples/empty_file.lox"),
-        ""
-    );
+    assert_eq!(interpret_file_stdout("examples/empty_file.lox"), "");
 }
 
 #[test]
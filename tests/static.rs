@@ -0,0 +1,68 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn callable_field() {
+    assert_eq!(
+        interpret_file_stdout("examples/static/callable_field.lox"),
+        "\"handled click\"\n"
+    );
+}
+
+#[test]
+fn field() {
+    assert_eq!(
+        interpret_file_stdout("examples/static/field.lox"),
+        "nil\n2\n\"counter\"\n"
+    );
+}
+
+#[test]
+fn inherit() {
+    assert_eq!(
+        interpret_file_stdout("examples/static/inherit.lox"),
+        "instance Derived\n\"I am base (derived)\"\n3\n\"I am shared (derived)\"\n"
+    );
+}
+
+#[test]
+fn instance_method_on_class() {
+    assert!(interpret_file_result("examples/static/instance_method_on_class.lox").is_err());
+}
+
+#[test]
+fn method() {
+    assert_eq!(
+        interpret_file_stdout("examples/static/method.lox"),
+        "9\n5\n16\n"
+    );
+}
+
+#[test]
+fn static_init() {
+    assert!(interpret_file_result("examples/static/static_init.lox").is_err());
+}
+
+#[test]
+fn static_on_instance() {
+    assert!(interpret_file_result("examples/static/static_on_instance.lox").is_err());
+}
+
+#[test]
+fn super_bound() {
+    assert_eq!(
+        interpret_file_stdout("examples/static/super_bound.lox"),
+        "\"AB\"\n"
+    );
+}
+
+#[test]
+fn this_in_field() {
+    assert!(interpret_file_result("examples/static/this_in_field.lox").is_err());
+}
+
+#[test]
+fn undeclared_field() {
+    assert!(interpret_file_result("examples/static/undeclared_field.lox").is_err());
+}
@@ -1,19 +1,69 @@
 mod test_utils;
 
+use rox::vm::InterpretError;
 use test_utils::{interpret_file_result, interpret_file_stdout};
 
+fn assert_compile_error(path: &str, expected: &str) {
+    match interpret_file_result(path) {
+        Err(InterpretError::CompileError(message)) => assert_eq!(message, expected),
+        result => panic!("Expected a compile error, got {:?}", result),
+    }
+}
+
 #[test]
 fn error_after_multiline() {
     assert!(interpret_file_result("examples/string/error_after_multiline.lox").is_err());
 }
 
-// #[test]
-// fn literals() {
-//     assert_eq!(
-//         interpret_file_stdout("examples/string/literals.lox"),
-//         "\"()\"\n\"a string\"\n\"A~¶Þॐஃ\"\n"
-//     );
-// }
+#[test]
+fn escapes() {
+    assert_eq!(
+        interpret_file_stdout("examples/string/escapes.lox"),
+        "\"tab:\there\"\n\"line\nbreak\"\n\"quote: \"hi\"\"\n\"backslash: \\\"\n\"dollar: ${not interpolated}\"\n\"Hé😀\"\n"
+    );
+}
+
+#[test]
+fn interpolation() {
+    assert_eq!(
+        interpret_file_stdout("examples/string/interpolation.lox"),
+        "\"Hello Lox!\"\n\"3 + 1 = 4\"\n\"nil true [1, \"two\"]\"\n\"Lox\"\n"
+    );
+}
+
+#[test]
+fn interpolation_nested() {
+    assert_eq!(
+        interpret_file_stdout("examples/string/interpolation_nested.lox"),
+        "\"outer inner b done\"\n\"hi you, lambda\"\n"
+    );
+}
+
+#[test]
+fn interpolation_values() {
+    assert_eq!(
+        interpret_file_stdout("examples/string/interpolation_values.lox"),
+        "\"Point instance Point fn add\"\n\"sum: 3.5\"\n"
+    );
+}
+
+#[test]
+fn invalid_escape() {
+    assert!(interpret_file_result("examples/string/invalid_escape.lox").is_err());
+}
+
+#[test]
+fn invalid_unicode_escape() {
+    assert!(interpret_file_result("examples/string/invalid_unicode_escape.lox").is_err());
+}
+
+#[test]
+fn literals() {
+    assert_eq!(
+        interpret_file_stdout("examples/string/literals.lox"),
+        "\"()\"\n\"a string\"\n\"A~¶Þॐஃ\"\n"
+    );
+}
 
 #[test]
 fn multiline() {
@@ -23,7 +73,44 @@ fn multiline() {
     );
 }
 
-// #[test]
-// fn unterminated() {
-//     assert!(interpret_file_result("examples/string/unterminated.lox").is_err());
-// }
+#[test]
+fn raw() {
+    assert_eq!(
+        interpret_file_stdout("examples/string/raw.lox"),
+        "\"C:\\path\\to\\${file}\"\n\"first\nsecond\"\n"
+    );
+}
+
+#[test]
+fn triple_quoted() {
+    assert_eq!(
+        interpret_file_stdout("examples/string/triple_quoted.lox"),
+        "\"He said \"hello, world\".\n\tDone\"\n"
+    );
+}
+
+#[test]
+fn unicode_escape_non_hex() {
+    assert_compile_error(
+        "examples/string/unicode_escape_non_hex.lox",
+        "[line 1] Error at \\u{41 rest}: Invalid Unicode escape '\\u{41'.",
+    );
+}
+
+#[test]
+fn unterminated() {
+    assert!(interpret_file_result("examples/string/unterminated.lox").is_err());
+}
+
+#[test]
+fn unterminated_interpolation() {
+    assert!(interpret_file_result("examples/string/unterminated_interpolation.lox").is_err());
+}
+
+#[test]
+fn unterminated_unicode_escape() {
+    assert_compile_error(
+        "examples/string/unterminated_unicode_escape.lox",
+        "[line 1] Error at \\u{1F600: Invalid Unicode escape '\\u{1F600'.",
+    );
+}
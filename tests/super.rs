@@ -131,3 +131,11 @@ fn missing_arguments() {
 fn super_in_top_level_function() {
     assert!(interpret_file_result("examples/super/super_in_top_level_function.lox").is_err());
 }
+
+#[test]
+fn get_super_locals() {
+    assert_eq!(
+        interpret_file_stdout("examples/super/get_super_locals.lox"),
+        "\"before\"\n\"A.method\"\n\"after\"\n"
+    );
+}
@@ -1,10 +1,12 @@
+#![allow(dead_code)]
+
 use gag::BufferRedirect;
 use rox::vm::InterpretError;
 use rox::{run::run, vm::VM};
 use std::fs;
 use std::io::Read;
 
-fn capture_stdout<F>(func: F) -> String
+pub fn capture_stdout<F>(func: F) -> String
 where
     F: FnOnce(),
 {
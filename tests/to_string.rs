@@ -0,0 +1,79 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn concatenation() {
+    assert_eq!(
+        interpret_file_stdout("examples/to_string/concatenation.lox"),
+        "\"price: $2.5\"\n\"$2.5 each\"\n\"interpolated: $2.5\"\n"
+    );
+}
+
+#[test]
+fn cyclic_instances() {
+    assert_eq!(
+        interpret_file_stdout("examples/to_string/cyclic_instances.lox"),
+        "a -> b -> instance Node\n"
+    );
+}
+
+#[test]
+fn cyclic_list() {
+    assert_eq!(
+        interpret_file_stdout("examples/to_string/cyclic_list.lox"),
+        "[1, 2, [...]]\n[[1, 2, [...]]]\n\"[1, 2, [...]]\"\n"
+    );
+}
+
+#[test]
+fn error_in_str() {
+    assert!(interpret_file_result("examples/to_string/error_in_str.lox").is_err());
+}
+
+#[test]
+fn error_in_to_string() {
+    assert!(interpret_file_result("examples/to_string/error_in_to_string.lox").is_err());
+}
+
+#[test]
+fn error_kind() {
+    assert_eq!(
+        interpret_file_stdout("examples/to_string/error_kind.lox"),
+        "instance PropertyError\n\"Undefined property 'missing'\"\ninstance PropertyError\n"
+    );
+}
+
+#[test]
+fn non_string_result() {
+    assert!(interpret_file_result("examples/to_string/non_string_result.lox").is_err());
+}
+
+#[test]
+fn print() {
+    assert_eq!(
+        interpret_file_stdout("examples/to_string/print.lox"),
+        "Point(1, 2)\n[Point(1, 2), Point(3, 4)]\n(Point(1, 2), \"label\")\n"
+    );
+}
+
+#[test]
+fn recursive_to_string() {
+    assert_eq!(
+        interpret_file_stdout("examples/to_string/recursive_to_string.lox"),
+        "root at instance Node\n"
+    );
+}
+
+#[test]
+fn str() {
+    assert_eq!(
+        interpret_file_stdout("examples/to_string/str.lox"),
+        "\"named\"\n\"instance Plain\"\n\"42\"\n\"text\"\n\"nil\"\n\"[1, \"two\"]\"\ntrue\n"
+    );
+}
+
+#[test]
+fn without_to_string() {
+    assert!(interpret_file_result("examples/to_string/without_to_string.lox").is_err());
+}
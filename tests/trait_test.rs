@@ -0,0 +1,87 @@
+mod test_utils;
+
+use test_utils::{interpret_file_result, interpret_file_stdout};
+
+#[test]
+fn accessors() {
+    assert_eq!(
+        interpret_file_stdout("examples/trait/accessors.lox"),
+        "true\n1\n"
+    );
+}
+
+#[test]
+fn basic() {
+    assert_eq!(
+        interpret_file_stdout("examples/trait/basic.lox"),
+        "\"Hello from Ada\"\n"
+    );
+}
+
+#[test]
+fn conflict() {
+    assert!(interpret_file_result("examples/trait/conflict.lox").is_err());
+}
+
+#[test]
+fn conflict_resolved() {
+    assert_eq!(
+        interpret_file_stdout("examples/trait/conflict_resolved.lox"),
+        "\"C\"\n"
+    );
+}
+
+#[test]
+fn implements() {
+    assert_eq!(
+        interpret_file_stdout("examples/trait/implements.lox"),
+        "true\ntrue\nfalse\nfalse\nfalse\ntrue\nfalse\nWalks\n\"trait\"\n"
+    );
+}
+
+#[test]
+fn initializer() {
+    assert!(interpret_file_result("examples/trait/initializer.lox").is_err());
+}
+
+#[test]
+fn multiple() {
+    assert_eq!(
+        interpret_file_stdout("examples/trait/multiple.lox"),
+        "true\nfalse\n\"<10 EUR>\"\n"
+    );
+}
+
+#[test]
+fn not_a_trait() {
+    assert!(interpret_file_result("examples/trait/not_a_trait.lox").is_err());
+}
+
+#[test]
+fn precedence() {
+    assert_eq!(
+        interpret_file_stdout("examples/trait/precedence.lox"),
+        "\"trait\"\n\"class\"\n"
+    );
+}
+
+#[test]
+fn super_in_trait() {
+    assert!(interpret_file_result("examples/trait/super_in_trait.lox").is_err());
+}
+
+#[test]
+fn this_in_trait() {
+    assert_eq!(
+        interpret_file_stdout("examples/trait/this_in_trait.lox"),
+        "2\n"
+    );
+}
+
+#[test]
+fn with_as_name() {
+    assert_eq!(
+        interpret_file_stdout("examples/trait/with_as_name.lox"),
+        "\"a variable\"\n\"hello\"\n\"a field\"\n"
+    );
+}
@@ -48,3 +48,11 @@ fn syntax() {
 fn var_in_body() {
     assert!(interpret_file_result("examples/while/var_in_body.lox").is_err());
 }
+
+#[test]
+fn local_after_loop() {
+    assert_eq!(
+        interpret_file_stdout("examples/while/local_after_loop.lox"),
+        "\"after\"\n"
+    );
+}